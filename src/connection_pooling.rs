@@ -0,0 +1,26 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+mod manage_connection;
+mod pool;
+mod pool_options;
+mod pool_status;
+mod pooled_connection;
+
+pub use manage_connection::ManageConnection;
+pub use pool::Pool;
+pub use pool_options::PoolOptions;
+pub use pool_status::PoolStatus;
+pub use pooled_connection::PooledConnection;
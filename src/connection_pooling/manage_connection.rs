@@ -0,0 +1,26 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+pub trait ManageConnection: Send + Sync + 'static {
+    type Connection: Send + 'static;
+
+    fn connect(&self) -> crate::Result<Self::Connection>;
+
+    fn is_valid(&self, connection: &mut Self::Connection) -> crate::Result<()>;
+
+    fn has_broken(&self, _connection: &mut Self::Connection) -> bool {
+        false
+    }
+}
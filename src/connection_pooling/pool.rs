@@ -0,0 +1,275 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    thread,
+    time::Instant,
+};
+
+use super::{ManageConnection, PoolOptions, PoolStatus, PooledConnection};
+
+pub struct Pool<Manager: ManageConnection> {
+    shared: Arc<SharedPool<Manager>>,
+}
+
+pub(super) struct SharedPool<Manager: ManageConnection> {
+    manager: Manager,
+    options: PoolOptions,
+    state: Mutex<PoolState<Manager::Connection>>,
+    condvar: Condvar,
+}
+
+struct PoolState<Connection> {
+    idle: VecDeque<IdleConnection<Connection>>,
+    connections: usize,
+}
+
+struct IdleConnection<Connection> {
+    connection: Connection,
+    created_at: Instant,
+    idle_since: Instant,
+}
+
+impl<Manager: ManageConnection> Pool<Manager> {
+    pub fn new(manager: Manager, options: PoolOptions) -> crate::Result<Self> {
+        options.validate()?;
+
+        let shared = Arc::new(SharedPool {
+            manager,
+            options,
+            state: Mutex::new(PoolState {
+                idle: VecDeque::new(),
+                connections: 0,
+            }),
+            condvar: Condvar::new(),
+        });
+
+        shared.fill_to_min_size()?;
+
+        if shared.options.max_lifetime.is_some() || shared.options.idle_timeout.is_some() {
+            spawn_reaper(&shared);
+        }
+
+        Ok(Self { shared })
+    }
+
+    pub fn get(&self) -> crate::Result<PooledConnection<Manager>> {
+        let deadline = Instant::now() + self.shared.options.acquire_timeout;
+        let mut state = self.shared.lock_state();
+
+        loop {
+            // Idle connections are handed out most recently used first so that the least recently
+            // used ones are left at the front of the queue for the reaper.
+            if let Some(idle) = state.idle.pop_back() {
+                drop(state);
+
+                if let Some(pooled) = self.shared.check_out(idle) {
+                    return Ok(pooled);
+                }
+
+                state = self.shared.lock_state();
+                continue;
+            }
+
+            if state.connections < self.shared.options.max_size {
+                state.connections += 1;
+                drop(state);
+
+                return match self.shared.manager.connect() {
+                    Ok(connection) => Ok(PooledConnection::new(
+                        self.shared.clone(),
+                        connection,
+                        Instant::now(),
+                    )),
+                    Err(error) => {
+                        self.shared.forget_connection();
+                        Err(error)
+                    }
+                };
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                return Err(crate::Error::PoolTimedOut {
+                    timeout: self.shared.options.acquire_timeout,
+                });
+            }
+
+            state = self
+                .shared
+                .condvar
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    pub fn status(&self) -> PoolStatus {
+        let state = self.shared.lock_state();
+
+        PoolStatus {
+            connections: state.connections,
+            idle_connections: state.idle.len(),
+        }
+    }
+
+    pub fn manager(&self) -> &Manager {
+        &self.shared.manager
+    }
+}
+
+impl<Manager: ManageConnection> Clone for Pool<Manager> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<Manager: ManageConnection> SharedPool<Manager> {
+    fn lock_state(&self) -> MutexGuard<'_, PoolState<Manager::Connection>> {
+        // The state is never left inconsistent by a panic, so poisoning can be safely ignored
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn is_expired(&self, created_at: Instant, now: Instant) -> bool {
+        self.options
+            .max_lifetime
+            .is_some_and(|max_lifetime| now.duration_since(created_at) >= max_lifetime)
+    }
+
+    fn check_out(
+        self: &Arc<Self>,
+        mut idle: IdleConnection<Manager::Connection>,
+    ) -> Option<PooledConnection<Manager>> {
+        if self.is_expired(idle.created_at, Instant::now())
+            || (self.options.test_on_checkout
+                && self.manager.is_valid(&mut idle.connection).is_err())
+        {
+            drop(idle);
+            self.forget_connection();
+            return None;
+        }
+
+        Some(PooledConnection::new(
+            self.clone(),
+            idle.connection,
+            idle.created_at,
+        ))
+    }
+
+    pub(super) fn release(&self, mut connection: Manager::Connection, created_at: Instant) {
+        let now = Instant::now();
+
+        if self.manager.has_broken(&mut connection) || self.is_expired(created_at, now) {
+            drop(connection);
+            self.forget_connection();
+            return;
+        }
+
+        self.lock_state().idle.push_back(IdleConnection {
+            connection,
+            created_at,
+            idle_since: now,
+        });
+        self.condvar.notify_one();
+    }
+
+    fn forget_connection(&self) {
+        self.lock_state().connections -= 1;
+        self.condvar.notify_one();
+    }
+
+    fn fill_to_min_size(&self) -> crate::Result<()> {
+        loop {
+            let mut state = self.lock_state();
+
+            if state.connections >= self.options.min_size {
+                return Ok(());
+            }
+
+            state.connections += 1;
+            drop(state);
+
+            match self.manager.connect() {
+                Ok(connection) => {
+                    let now = Instant::now();
+
+                    self.lock_state().idle.push_back(IdleConnection {
+                        connection,
+                        created_at: now,
+                        idle_since: now,
+                    });
+                    self.condvar.notify_one();
+                }
+                Err(error) => {
+                    self.forget_connection();
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    fn reap(&self) {
+        let now = Instant::now();
+        let mut state = self.lock_state();
+        let mut reaped = Vec::new();
+        let mut kept = VecDeque::with_capacity(state.idle.len());
+
+        // Walk from the most recently used connection so that the ones kept around to satisfy the
+        // minimum size are the freshest.
+        while let Some(idle) = state.idle.pop_back() {
+            let idle_too_long = self
+                .options
+                .idle_timeout
+                .is_some_and(|idle_timeout| now.duration_since(idle.idle_since) >= idle_timeout);
+
+            if self.is_expired(idle.created_at, now)
+                || (idle_too_long && state.connections - reaped.len() > self.options.min_size)
+            {
+                reaped.push(idle);
+            } else {
+                kept.push_front(idle);
+            }
+        }
+
+        state.idle = kept;
+        state.connections -= reaped.len();
+        drop(state);
+
+        drop(reaped);
+        self.condvar.notify_all();
+
+        // Failing to replenish is not fatal, the next checkout will simply open a new connection
+        let _ = self.fill_to_min_size();
+    }
+}
+
+fn spawn_reaper<Manager: ManageConnection>(shared: &Arc<SharedPool<Manager>>) {
+    let reap_interval = shared.options.reap_interval;
+    let shared = Arc::downgrade(shared);
+
+    thread::spawn(move || loop {
+        thread::sleep(reap_interval);
+
+        match shared.upgrade() {
+            Some(shared) => shared.reap(),
+            None => break,
+        }
+    });
+}
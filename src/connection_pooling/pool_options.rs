@@ -0,0 +1,112 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+const DEFAULT_MIN_SIZE: usize = 0;
+const DEFAULT_MAX_SIZE: usize = 10;
+const DEFAULT_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolOptions {
+    pub(super) min_size: usize,
+    pub(super) max_size: usize,
+    pub(super) acquire_timeout: Duration,
+    pub(super) test_on_checkout: bool,
+    pub(super) max_lifetime: Option<Duration>,
+    pub(super) idle_timeout: Option<Duration>,
+    pub(super) reap_interval: Duration,
+}
+
+impl PoolOptions {
+    pub fn new() -> Self {
+        Self {
+            min_size: DEFAULT_MIN_SIZE,
+            max_size: DEFAULT_MAX_SIZE,
+            acquire_timeout: DEFAULT_ACQUIRE_TIMEOUT,
+            test_on_checkout: true,
+            max_lifetime: None,
+            idle_timeout: None,
+            reap_interval: DEFAULT_REAP_INTERVAL,
+        }
+    }
+
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn acquire_timeout(mut self, acquire_timeout: Duration) -> Self {
+        self.acquire_timeout = acquire_timeout;
+        self
+    }
+
+    pub fn test_on_checkout(mut self, test_on_checkout: bool) -> Self {
+        self.test_on_checkout = test_on_checkout;
+        self
+    }
+
+    pub fn max_lifetime(mut self, max_lifetime: Option<Duration>) -> Self {
+        self.max_lifetime = max_lifetime;
+        self
+    }
+
+    pub fn idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    pub fn reap_interval(mut self, reap_interval: Duration) -> Self {
+        self.reap_interval = reap_interval;
+        self
+    }
+
+    pub(super) fn validate(&self) -> crate::Result<()> {
+        if self.max_size == 0 {
+            return Err(crate::Error::InvalidPoolOptions {
+                message: "max size must be at least 1".to_owned(),
+            });
+        }
+
+        if self.min_size > self.max_size {
+            return Err(crate::Error::InvalidPoolOptions {
+                message: format!(
+                    "min size {} is greater than max size {}",
+                    self.min_size, self.max_size
+                ),
+            });
+        }
+
+        if self.reap_interval.is_zero() {
+            return Err(crate::Error::InvalidPoolOptions {
+                message: "reap interval must be non-zero".to_owned(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
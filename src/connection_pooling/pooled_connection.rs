@@ -0,0 +1,68 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+    time::Instant,
+};
+
+use super::{pool::SharedPool, ManageConnection};
+
+pub struct PooledConnection<Manager: ManageConnection> {
+    pool: Arc<SharedPool<Manager>>,
+    connection: Option<Manager::Connection>,
+    created_at: Instant,
+}
+
+impl<Manager: ManageConnection> PooledConnection<Manager> {
+    pub(super) fn new(
+        pool: Arc<SharedPool<Manager>>,
+        connection: Manager::Connection,
+        created_at: Instant,
+    ) -> Self {
+        Self {
+            pool,
+            connection: Some(connection),
+            created_at,
+        }
+    }
+}
+
+impl<Manager: ManageConnection> Deref for PooledConnection<Manager> {
+    type Target = Manager::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.connection
+            .as_ref()
+            .expect("connection is only taken when dropped")
+    }
+}
+
+impl<Manager: ManageConnection> DerefMut for PooledConnection<Manager> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.connection
+            .as_mut()
+            .expect("connection is only taken when dropped")
+    }
+}
+
+impl<Manager: ManageConnection> Drop for PooledConnection<Manager> {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.release(connection, self.created_at);
+        }
+    }
+}
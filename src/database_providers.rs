@@ -13,6 +13,32 @@
 // You should have received a copy of the GNU General Public License along with Bedrock ORM. If
 // not, see <https://www.gnu.org/licenses/>.
 
+mod async_sqlite;
+mod integer_overflow_policy;
+mod mock;
+#[cfg(feature = "recording")]
+mod recording;
+mod retry_metrics;
+mod retry_policy;
 mod sqlite;
+mod sqlite_bulk_insert;
+mod sqlite_connection_manager;
+mod sqlite_errors;
+mod sqlite_exclusive_access;
+mod sqlite_interrupt;
+#[cfg(feature = "uuid")]
+mod uuid_storage;
 
-pub use sqlite::{SqliteConnection, SqliteQuery};
+pub use async_sqlite::{AsyncSqliteConnection, AsyncSqliteQuery, AsyncSqliteRowStream};
+pub use integer_overflow_policy::IntegerOverflowPolicy;
+pub use mock::{MockConnection, MockExpectation, MockQuery, MockRow, MockRowIterator};
+#[cfg(feature = "recording")]
+pub use recording::{RecordingConnection, RecordingQuery};
+pub use retry_metrics::RetryMetrics;
+pub use retry_policy::RetryPolicy;
+pub use sqlite::{SqliteConnection, SqliteQuery, SqliteRow, SqliteRowIterator};
+pub use sqlite_connection_manager::SqliteConnectionManager;
+#[cfg(feature = "uuid")]
+pub use uuid_storage::UuidStorage;
+
+pub(crate) use sqlite_errors::classify_sqlite_error;
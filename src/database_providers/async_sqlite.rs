@@ -0,0 +1,380 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use futures::{
+    channel::{mpsc, oneshot},
+    executor::block_on,
+    future::poll_fn,
+    Stream, StreamExt,
+};
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    domain::{OwnedValueUnion, ValueUnion},
+    query_building::Dialect,
+    query_execution::{
+        AsyncExecuteQuery, CancelHandle, ExecuteQuery, GetDialect, GetQueryResultType,
+        InjectFeatures, QueryResultType,
+    },
+};
+
+use super::{
+    sqlite::{validate_identifier, OwnedSqliteRowIterator},
+    SqliteConnection, SqliteQuery, SqliteRow,
+};
+
+const ROW_BUFFER_SIZE: usize = 64;
+
+type RowSender = mpsc::Sender<Result<SqliteRow, sqlite::Error>>;
+
+enum Job {
+    Run(Box<dyn FnOnce(&SqliteConnection) + Send>),
+    Stream {
+        query: AsyncSqliteQuery,
+        started: oneshot::Sender<crate::Result<CancelHandle>>,
+        rows: RowSender,
+    },
+}
+
+struct OpenStream {
+    row_iterator: OwnedSqliteRowIterator,
+    rows: RowSender,
+}
+
+enum WorkerEvent {
+    Job(Job),
+    JobsClosed,
+    StreamReady(usize),
+    StreamClosed(usize),
+}
+
+#[derive(Clone)]
+pub struct AsyncSqliteConnection {
+    jobs: mpsc::UnboundedSender<Job>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AsyncSqliteQuery {
+    query_text: String,
+    result_type: QueryResultType,
+    bindings: Vec<(String, OwnedValueUnion)>,
+    timeout: Option<Duration>,
+}
+
+pub struct AsyncSqliteRowStream {
+    rows: mpsc::Receiver<Result<SqliteRow, sqlite::Error>>,
+    cancel_handle: CancelHandle,
+}
+
+impl AsyncSqliteConnection {
+    pub async fn connect_memory() -> crate::Result<Self> {
+        Self::connect_path(PathBuf::from(":memory:")).await
+    }
+
+    pub async fn connect_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        Self::connect_path(path.as_ref().to_owned()).await
+    }
+
+    async fn connect_path(path: PathBuf) -> crate::Result<Self> {
+        let (jobs, job_receiver) = mpsc::unbounded::<Job>();
+        let (connected_sender, connected) = oneshot::channel();
+
+        // Each connection gets its own worker thread which owns the underlying SQLite connection,
+        // so blocking calls never run on the async executor. Open row streams are stepped on the
+        // same thread whenever their reader has room for another row.
+        thread::spawn(move || {
+            let connection = match SqliteConnection::connect_path(&path) {
+                Ok(connection) => {
+                    let _ = connected_sender.send(Ok(()));
+                    connection
+                }
+                Err(error) => {
+                    let _ = connected_sender.send(Err(error));
+                    return;
+                }
+            };
+
+            block_on(run_worker(&connection, job_receiver));
+        });
+
+        connected
+            .await
+            .map_err(|_| crate::Error::ConnectionWorkerStopped)??;
+
+        Ok(Self { jobs })
+    }
+
+    fn run<Value, Run>(&self, run: Run) -> impl Future<Output = crate::Result<Value>> + Send
+    where
+        Value: Send + 'static,
+        Run: FnOnce(&SqliteConnection) -> crate::Result<Value> + Send + 'static,
+    {
+        let jobs = self.jobs.clone();
+
+        async move {
+            let (result_sender, result) = oneshot::channel();
+
+            dispatch(
+                &jobs,
+                Job::Run(Box::new(move |connection| {
+                    let _ = result_sender.send(run(connection));
+                })),
+            )?;
+
+            result
+                .await
+                .map_err(|_| crate::Error::ConnectionWorkerStopped)?
+        }
+    }
+}
+
+fn dispatch(jobs: &mpsc::UnboundedSender<Job>, job: Job) -> crate::Result<()> {
+    jobs.unbounded_send(job)
+        .map_err(|_| crate::Error::ConnectionWorkerStopped)
+}
+
+async fn run_worker(connection: &SqliteConnection, mut jobs: mpsc::UnboundedReceiver<Job>) {
+    let mut streams = Vec::<OpenStream>::new();
+    let mut jobs_closed = false;
+
+    // Streams may outlive every handle to the connection, so the worker keeps going until they
+    // have all been read or dropped
+    while !jobs_closed || !streams.is_empty() {
+        let event = poll_fn(|context| {
+            if !jobs_closed {
+                match jobs.poll_next_unpin(context) {
+                    Poll::Ready(Some(job)) => return Poll::Ready(WorkerEvent::Job(job)),
+                    Poll::Ready(None) => return Poll::Ready(WorkerEvent::JobsClosed),
+                    Poll::Pending => {}
+                }
+            }
+
+            for (index, stream) in streams.iter_mut().enumerate() {
+                match stream.rows.poll_ready(context) {
+                    Poll::Ready(Ok(())) => return Poll::Ready(WorkerEvent::StreamReady(index)),
+                    Poll::Ready(Err(_)) => return Poll::Ready(WorkerEvent::StreamClosed(index)),
+                    Poll::Pending => {}
+                }
+            }
+
+            Poll::Pending
+        })
+        .await;
+
+        match event {
+            WorkerEvent::Job(Job::Run(run)) => run(connection),
+            WorkerEvent::Job(Job::Stream {
+                query,
+                started,
+                rows,
+            }) => match query.prepare(connection) {
+                Ok(prepared) => {
+                    let row_iterator = prepared.into_row_iterator();
+                    let _ = started.send(Ok(row_iterator.cancel_handle()));
+
+                    streams.push(OpenStream { row_iterator, rows });
+                }
+                Err(error) => {
+                    let _ = started.send(Err(error));
+                }
+            },
+            WorkerEvent::JobsClosed => jobs_closed = true,
+            WorkerEvent::StreamReady(index) => {
+                // Ready streams take turns one row at a time, so a fast reader cannot starve the
+                // others
+                let mut stream = streams.remove(index);
+
+                if let Some(row) = stream.row_iterator.next() {
+                    if stream.rows.start_send(row).is_ok() {
+                        streams.push(stream);
+                    }
+                }
+            }
+            WorkerEvent::StreamClosed(index) => {
+                streams.remove(index);
+            }
+        }
+    }
+}
+
+impl GetDialect for AsyncSqliteConnection {
+    fn dialect(&self) -> Dialect {
+        Dialect::Sqlite
+    }
+}
+
+impl AsyncExecuteQuery for AsyncSqliteConnection {
+    type Query = AsyncSqliteQuery;
+    type Row = Result<SqliteRow, sqlite::Error>;
+    type RowStream = AsyncSqliteRowStream;
+
+    fn execute_without_results(
+        &self,
+        query: &mut Self::Query,
+    ) -> impl Future<Output = crate::Result<()>> + Send {
+        let query = query.clone();
+
+        self.run(move |connection| {
+            let mut prepared = query.prepare(connection)?;
+            connection.execute_without_results(&mut prepared)
+        })
+    }
+
+    fn execute_with_change_count(
+        &self,
+        query: &mut Self::Query,
+    ) -> impl Future<Output = crate::Result<usize>> + Send {
+        let query = query.clone();
+
+        self.run(move |connection| {
+            let mut prepared = query.prepare(connection)?;
+            connection.execute_with_change_count(&mut prepared)
+        })
+    }
+
+    fn execute_with_iterator(
+        &self,
+        query: &mut Self::Query,
+    ) -> impl Future<Output = crate::Result<Self::RowStream>> + Send {
+        let query = query.clone();
+        let jobs = self.jobs.clone();
+
+        async move {
+            let (started_sender, started) = oneshot::channel();
+            let (row_sender, rows) = mpsc::channel(ROW_BUFFER_SIZE);
+
+            dispatch(
+                &jobs,
+                Job::Stream {
+                    query,
+                    started: started_sender,
+                    rows: row_sender,
+                },
+            )?;
+
+            let cancel_handle = started
+                .await
+                .map_err(|_| crate::Error::ConnectionWorkerStopped)??;
+
+            Ok(AsyncSqliteRowStream {
+                rows,
+                cancel_handle,
+            })
+        }
+    }
+}
+
+impl AsyncSqliteQuery {
+    fn new_with_result_type(query_text: &str, result_type: QueryResultType) -> Self {
+        Self {
+            query_text: query_text.to_owned(),
+            result_type,
+            bindings: Vec::new(),
+            timeout: None,
+        }
+    }
+
+    pub fn new_without_results(query_text: &str) -> Self {
+        Self::new_with_result_type(query_text, QueryResultType::None)
+    }
+
+    pub fn new_with_change_count(query_text: &str) -> Self {
+        Self::new_with_result_type(query_text, QueryResultType::ChangeCount)
+    }
+
+    pub fn new_with_iterator(query_text: &str) -> Self {
+        Self::new_with_result_type(query_text, QueryResultType::Iterator)
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    fn prepare(&self, connection: &SqliteConnection) -> crate::Result<SqliteQuery> {
+        let mut query =
+            SqliteQuery::new_with_result_type(connection, &self.query_text, self.result_type)?;
+        query.set_timeout(self.timeout);
+
+        for (identifier, value) in &self.bindings {
+            query.inject_feature(identifier, &value.as_value_union())?;
+        }
+
+        Ok(query)
+    }
+}
+
+impl GetQueryResultType for AsyncSqliteQuery {
+    fn query_result_type(&self) -> QueryResultType {
+        self.result_type
+    }
+}
+
+impl InjectFeatures for AsyncSqliteQuery {
+    type Identifier = String;
+
+    fn inject_feature(
+        &mut self,
+        identifier: &Self::Identifier,
+        value: &ValueUnion,
+    ) -> crate::Result<()> {
+        validate_identifier(identifier)?;
+
+        let value = OwnedValueUnion::from(value);
+
+        match self
+            .bindings
+            .iter_mut()
+            .find(|(existing_identifier, _)| existing_identifier == identifier)
+        {
+            Some((_, existing_value)) => *existing_value = value,
+            None => self.bindings.push((identifier.clone(), value)),
+        }
+
+        Ok(())
+    }
+}
+
+impl Stream for AsyncSqliteRowStream {
+    type Item = Result<SqliteRow, sqlite::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rows.poll_next_unpin(context)
+    }
+}
+
+impl AsyncSqliteRowStream {
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+}
+
+impl Drop for AsyncSqliteRowStream {
+    fn drop(&mut self) {
+        // The worker only notices a dropped stream between rows, so a long scan is interrupted
+        // instead of holding up the worker until it finds the next row
+        self.cancel_handle.cancel();
+    }
+}
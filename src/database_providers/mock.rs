@@ -0,0 +1,474 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(feature = "recording")]
+use std::path::Path;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    vec,
+};
+
+#[cfg(feature = "recording")]
+use super::recording::read_recording;
+use super::sqlite::validate_identifier;
+use crate::{
+    domain::{OwnedValueUnion, SqlType, ValueUnion},
+    query_building::Dialect,
+    query_execution::{
+        CancelHandle, ExecuteQuery, GetDialect, GetQueryResultType, InjectFeatures, ListFeatures,
+        PrepareQuery, QueryResultType, RowStream, TakeFeatures,
+    },
+};
+
+pub(super) enum MockResult {
+    None,
+    ChangeCount(usize),
+    Rows {
+        column_names: Arc<[String]>,
+        rows: Vec<Vec<OwnedValueUnion>>,
+    },
+    Error(crate::Error),
+    PrepareError(crate::Error),
+}
+
+pub struct MockExpectation {
+    pub(super) query_text: String,
+    pub(super) parameters: BTreeMap<String, OwnedValueUnion>,
+    pub(super) result: MockResult,
+}
+
+#[derive(Clone)]
+pub struct MockConnection {
+    expectations: Arc<Mutex<VecDeque<MockExpectation>>>,
+    dialect: Dialect,
+}
+
+pub struct MockQuery {
+    query_text: String,
+    result_type: QueryResultType,
+    parameters: BTreeMap<String, OwnedValueUnion>,
+}
+
+pub struct MockRow {
+    column_names: Arc<[String]>,
+    values: Vec<OwnedValueUnion>,
+}
+
+pub struct MockRowIterator {
+    column_names: Arc<[String]>,
+    rows: vec::IntoIter<MockRow>,
+    cancel_handle: CancelHandle,
+}
+
+impl MockExpectation {
+    pub fn new(query_text: &str) -> Self {
+        Self {
+            query_text: query_text.to_owned(),
+            parameters: BTreeMap::new(),
+            result: MockResult::None,
+        }
+    }
+
+    pub fn parameter<Value: SqlType>(mut self, name: &str, value: Value) -> Self {
+        self.parameters
+            .insert(name.to_owned(), value.to_sql_value());
+        self
+    }
+
+    pub fn returning_change_count(mut self, count: usize) -> Self {
+        self.result = MockResult::ChangeCount(count);
+        self
+    }
+
+    pub fn returning_rows(
+        mut self,
+        column_names: &[&str],
+        rows: Vec<Vec<OwnedValueUnion>>,
+    ) -> Self {
+        self.result = MockResult::Rows {
+            column_names: column_names
+                .iter()
+                .map(|column_name| (*column_name).to_owned())
+                .collect(),
+            rows,
+        };
+        self
+    }
+
+    pub fn failing_with(mut self, error: crate::Error) -> Self {
+        self.result = MockResult::Error(error);
+        self
+    }
+
+    // Fails `prepare_query` rather than the execution, so no parameters are expected
+    pub fn failing_to_prepare_with(mut self, error: crate::Error) -> Self {
+        self.result = MockResult::PrepareError(error);
+        self
+    }
+
+    fn check(&self, query: &MockQuery) -> crate::Result<()> {
+        if self.query_text != query.query_text {
+            return Err(crate::Error::UnexpectedQuery {
+                message: format!(
+                    "expected query {:?} but got {:?}",
+                    self.query_text, query.query_text
+                ),
+            });
+        }
+
+        // Values are compared semantically so that an `i32` parameter matches an expected `i64`
+        let parameters_match = self.parameters.len() == query.parameters.len()
+            && self.parameters.iter().all(|(name, expected)| {
+                query
+                    .parameters
+                    .get(name)
+                    .is_some_and(|actual| expected.semantically_eq(actual))
+            });
+
+        if !parameters_match {
+            return Err(crate::Error::UnexpectedQuery {
+                message: format!(
+                    "expected parameters {} but got {} for query {:?}",
+                    format_parameters(&self.parameters),
+                    format_parameters(&query.parameters),
+                    query.query_text
+                ),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn format_parameters(parameters: &BTreeMap<String, OwnedValueUnion>) -> String {
+    let parameters = parameters
+        .iter()
+        .map(|(name, value)| format!(":{} = {}", name, value))
+        .collect::<Vec<_>>();
+
+    format!("[{}]", parameters.join(", "))
+}
+
+impl MockConnection {
+    pub fn new() -> Self {
+        Self::with_dialect(Dialect::Sqlite)
+    }
+
+    pub fn with_dialect(dialect: Dialect) -> Self {
+        Self {
+            expectations: Arc::new(Mutex::new(VecDeque::new())),
+            dialect,
+        }
+    }
+
+    #[cfg(feature = "recording")]
+    pub fn replay<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let (dialect, expectations) = read_recording(path)?;
+        let connection = Self::with_dialect(dialect);
+
+        for expectation in expectations {
+            connection.expect(expectation);
+        }
+
+        Ok(connection)
+    }
+
+    pub fn expect(&self, expectation: MockExpectation) {
+        self.lock_expectations().push_back(expectation);
+    }
+
+    pub fn verify(&self) -> crate::Result<()> {
+        let expectations = self.lock_expectations();
+
+        if expectations.is_empty() {
+            return Ok(());
+        }
+
+        Err(crate::Error::UnmetExpectations {
+            queries: expectations
+                .iter()
+                .map(|expectation| expectation.query_text.clone())
+                .collect(),
+        })
+    }
+
+    fn lock_expectations(&self) -> MutexGuard<'_, VecDeque<MockExpectation>> {
+        // The queue is never left inconsistent by a panic, so poisoning can be safely ignored
+        self.expectations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn next_result(&self, query: &MockQuery) -> crate::Result<MockResult> {
+        let mut expectations = self.lock_expectations();
+
+        let expectation =
+            expectations
+                .pop_front()
+                .ok_or_else(|| crate::Error::UnexpectedQuery {
+                    message: format!(
+                        "no more queries were expected but got {:?}",
+                        query.query_text
+                    ),
+                })?;
+
+        // A mismatched expectation goes back on the queue, so `verify` still reports it as unmet
+        if let Err(error) = expectation.check(query) {
+            expectations.push_front(expectation);
+            return Err(error);
+        }
+
+        match expectation.result {
+            MockResult::Error(error) | MockResult::PrepareError(error) => Err(error),
+            result => Ok(result),
+        }
+    }
+}
+
+impl Default for MockConnection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockQuery {
+    fn new_with_result_type(
+        _connection: &MockConnection,
+        query_text: &str,
+        result_type: QueryResultType,
+    ) -> Self {
+        Self {
+            query_text: query_text.to_owned(),
+            result_type,
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    pub fn new_without_results(connection: &MockConnection, query_text: &str) -> Self {
+        Self::new_with_result_type(connection, query_text, QueryResultType::None)
+    }
+
+    pub fn new_with_change_count(connection: &MockConnection, query_text: &str) -> Self {
+        Self::new_with_result_type(connection, query_text, QueryResultType::ChangeCount)
+    }
+
+    pub fn new_with_iterator(connection: &MockConnection, query_text: &str) -> Self {
+        Self::new_with_result_type(connection, query_text, QueryResultType::Iterator)
+    }
+
+    pub fn query_text(&self) -> &str {
+        &self.query_text
+    }
+}
+
+impl GetQueryResultType for MockQuery {
+    fn query_result_type(&self) -> QueryResultType {
+        self.result_type
+    }
+}
+
+impl InjectFeatures for MockQuery {
+    type Identifier = String;
+
+    fn inject_feature(
+        &mut self,
+        identifier: &Self::Identifier,
+        value: &ValueUnion,
+    ) -> crate::Result<()> {
+        validate_identifier(identifier)?;
+
+        self.parameters
+            .insert(identifier.clone(), OwnedValueUnion::from(value));
+
+        Ok(())
+    }
+}
+
+impl GetDialect for MockConnection {
+    fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+}
+
+impl ExecuteQuery for MockConnection {
+    type Query = MockQuery;
+    type Row = MockRow;
+    type RowIterator<'query> = MockRowIterator;
+
+    fn execute_without_results(&self, query: &mut Self::Query) -> crate::Result<()> {
+        self.next_result(query)?;
+
+        Ok(())
+    }
+
+    fn execute_with_change_count(&self, query: &mut Self::Query) -> crate::Result<usize> {
+        match self.next_result(query)? {
+            MockResult::ChangeCount(count) => Ok(count),
+            _ => Err(crate::Error::UnexpectedQuery {
+                message: format!(
+                    "query {:?} was executed for a change count but the expectation does not \
+                     return one",
+                    query.query_text
+                ),
+            }),
+        }
+    }
+
+    fn execute_with_iterator<'query>(
+        &self,
+        query: &'query mut Self::Query,
+    ) -> crate::Result<Self::RowIterator<'query>> {
+        let MockResult::Rows { column_names, rows } = self.next_result(query)? else {
+            return Err(crate::Error::UnexpectedQuery {
+                message: format!(
+                    "query {:?} was executed for rows but the expectation does not return any",
+                    query.query_text
+                ),
+            });
+        };
+
+        MockRowIterator::new(column_names, rows)
+    }
+}
+
+impl PrepareQuery for MockConnection {
+    fn prepare_query(
+        &self,
+        query_text: &str,
+        query_result_type: QueryResultType,
+    ) -> crate::Result<Self::Query> {
+        let mut expectations = self.lock_expectations();
+
+        let fails_to_prepare = matches!(
+            expectations.front(),
+            Some(MockExpectation {
+                query_text: expected_query_text,
+                result: MockResult::PrepareError(_),
+                ..
+            }) if expected_query_text == query_text
+        );
+
+        if fails_to_prepare {
+            if let Some(MockExpectation {
+                result: MockResult::PrepareError(error),
+                ..
+            }) = expectations.pop_front()
+            {
+                return Err(error);
+            }
+        }
+
+        drop(expectations);
+
+        Ok(MockQuery::new_with_result_type(
+            self,
+            query_text,
+            query_result_type,
+        ))
+    }
+}
+
+impl MockRowIterator {
+    pub(super) fn new(
+        column_names: Arc<[String]>,
+        rows: Vec<Vec<OwnedValueUnion>>,
+    ) -> crate::Result<Self> {
+        let rows = rows
+            .into_iter()
+            .map(|values| {
+                if values.len() != column_names.len() {
+                    return Err(crate::Error::RowLengthMismatch {
+                        expected: column_names.len(),
+                        actual: values.len(),
+                    });
+                }
+
+                Ok(MockRow {
+                    column_names: column_names.clone(),
+                    values,
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            column_names,
+            rows: rows.into_iter(),
+            cancel_handle: CancelHandle::new(),
+        })
+    }
+}
+
+impl Iterator for MockRowIterator {
+    type Item = MockRow;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cancel_handle.is_cancelled() {
+            return None;
+        }
+
+        self.rows.next()
+    }
+}
+
+impl RowStream for MockRowIterator {
+    fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+}
+
+impl MockRow {
+    pub fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+}
+
+impl TakeFeatures for MockRow {
+    type Identifier = String;
+
+    fn take_feature(&self, identifier: &Self::Identifier) -> crate::Result<Option<ValueUnion<'_>>> {
+        let Some(index) = self
+            .column_names
+            .iter()
+            .position(|column_name| column_name == identifier)
+        else {
+            return Err(crate::Error::FeatureNotFound {
+                feature_name: identifier.clone(),
+            });
+        };
+
+        self.take_feature_at(index)
+    }
+}
+
+impl ListFeatures for MockRow {
+    fn feature_identifiers(&self) -> crate::Result<Vec<Self::Identifier>> {
+        Ok(self.column_names.to_vec())
+    }
+
+    fn take_feature_at(&self, index: usize) -> crate::Result<Option<ValueUnion<'_>>> {
+        match self.values.get(index) {
+            Some(OwnedValueUnion::Null) => Ok(None),
+            Some(value) => Ok(Some(value.as_value_union())),
+            None => Err(crate::Error::FeatureNotFound {
+                feature_name: index.to_string(),
+            }),
+        }
+    }
+}
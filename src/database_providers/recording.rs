@@ -0,0 +1,664 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use serde_json::{json, Map, Value as JsonValue};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use super::{mock::MockResult, MockExpectation, MockRow, MockRowIterator};
+use crate::{
+    domain::{DataType, OwnedValueUnion, ValueUnion},
+    query_building::Dialect,
+    query_execution::{
+        ExecuteQuery, GetDialect, GetQueryResultType, InjectFeatures, ListFeatures, PrepareQuery,
+        QueryResultType, RowStream,
+    },
+};
+
+const RECORDING_HEADER: &str = "bedrock-recording 3";
+
+const NAIVE_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+#[derive(Clone)]
+pub struct RecordingConnection<Connection> {
+    connection: Connection,
+    file: Arc<Mutex<File>>,
+}
+
+pub struct RecordingQuery<Query> {
+    query: Query,
+    query_text: String,
+    parameters: BTreeMap<String, OwnedValueUnion>,
+}
+
+impl<Connection: GetDialect> RecordingConnection<Connection> {
+    pub fn new<P: AsRef<Path>>(connection: Connection, path: P) -> crate::Result<Self> {
+        let mut file = File::create(path)?;
+
+        // The dialect is kept so that replaying builds the same query text as recording did
+        writeln!(
+            file,
+            "{} {}",
+            RECORDING_HEADER,
+            dialect_name(connection.dialect())
+        )?;
+
+        Ok(Self {
+            connection,
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+}
+
+impl<Connection> RecordingConnection<Connection> {
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    fn record<Value>(
+        &self,
+        query_text: &str,
+        parameters: &BTreeMap<String, OwnedValueUnion>,
+        result: &crate::Result<Value>,
+        encode_result: impl FnOnce(&Value) -> Vec<JsonValue>,
+    ) -> crate::Result<()> {
+        let mut lines = vec![encode_query(query_text, parameters)];
+
+        match result {
+            Ok(value) => lines.extend(encode_result(value)),
+            Err(error) => lines.push(json!({ "error": encode_error(error) })),
+        }
+
+        self.write_entry(lines)
+    }
+
+    fn write_entry(&self, lines: Vec<JsonValue>) -> crate::Result<()> {
+        let mut entry = String::new();
+
+        for line in lines {
+            entry.push_str(&line.to_string());
+            entry.push('\n');
+        }
+
+        // Each entry is written with a single call so that concurrent queries cannot interleave
+        self.lock_file().write_all(entry.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn lock_file(&self) -> MutexGuard<'_, File> {
+        // The file is never left inconsistent by a panic, so poisoning can be safely ignored
+        self.file.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<Query> RecordingQuery<Query> {
+    pub fn query(&self) -> &Query {
+        &self.query
+    }
+
+    pub fn query_text(&self) -> &str {
+        &self.query_text
+    }
+}
+
+impl<Query: GetQueryResultType> GetQueryResultType for RecordingQuery<Query> {
+    fn query_result_type(&self) -> QueryResultType {
+        self.query.query_result_type()
+    }
+}
+
+impl<Query> InjectFeatures for RecordingQuery<Query>
+where
+    Query: InjectFeatures<Identifier = String>,
+{
+    type Identifier = String;
+
+    fn inject_feature(
+        &mut self,
+        identifier: &Self::Identifier,
+        value: &ValueUnion,
+    ) -> crate::Result<()> {
+        self.query.inject_feature(identifier, value)?;
+        self.parameters
+            .insert(identifier.clone(), OwnedValueUnion::from(value));
+
+        Ok(())
+    }
+}
+
+impl<Connection: GetDialect> GetDialect for RecordingConnection<Connection> {
+    fn dialect(&self) -> Dialect {
+        self.connection.dialect()
+    }
+}
+
+impl<Connection> ExecuteQuery for RecordingConnection<Connection>
+where
+    Connection: ExecuteQuery,
+    Connection::Query: InjectFeatures<Identifier = String>,
+    Connection::Row: ListFeatures<Identifier = String>,
+{
+    type Query = RecordingQuery<Connection::Query>;
+    type Row = MockRow;
+    type RowIterator<'query>
+        = MockRowIterator
+    where
+        Self: 'query;
+
+    fn execute_without_results(&self, query: &mut Self::Query) -> crate::Result<()> {
+        let result = self.connection.execute_without_results(&mut query.query);
+        self.record(&query.query_text, &query.parameters, &result, |_| {
+            Vec::new()
+        })?;
+
+        result
+    }
+
+    fn execute_with_change_count(&self, query: &mut Self::Query) -> crate::Result<usize> {
+        let result = self.connection.execute_with_change_count(&mut query.query);
+        self.record(&query.query_text, &query.parameters, &result, |count| {
+            vec![json!({ "count": count })]
+        })?;
+
+        result
+    }
+
+    // The iterator type is named directly because the wrapped connection is not known to outlive
+    // the borrow of the query
+    fn execute_with_iterator(&self, query: &mut Self::Query) -> crate::Result<MockRowIterator> {
+        // Rows are buffered rather than streamed, so that each result is written as one entry
+        // that concurrent queries cannot interleave with. The whole result is therefore read
+        // before anything is returned, and the returned iterator's cancel handle has nothing left
+        // to cancel, while the wrapped query's timeout still applies to reading the rows. An error
+        // part way through is recorded in place of the rows read so far.
+        let result = self
+            .connection
+            .execute_with_iterator(&mut query.query)
+            .and_then(read_rows);
+
+        self.record(
+            &query.query_text,
+            &query.parameters,
+            &result,
+            |(column_names, rows)| {
+                let mut lines = vec![json!({ "columns": column_names })];
+
+                for values in rows {
+                    let values = values.iter().map(encode_value).collect::<Vec<_>>();
+                    lines.push(json!({ "row": values }));
+                }
+
+                lines
+            },
+        )?;
+
+        let (column_names, rows) = result?;
+
+        MockRowIterator::new(column_names.into(), rows)
+    }
+}
+
+impl<Connection> PrepareQuery for RecordingConnection<Connection>
+where
+    Connection: PrepareQuery,
+    Connection::Query: InjectFeatures<Identifier = String>,
+    Connection::Row: ListFeatures<Identifier = String>,
+{
+    fn prepare_query(
+        &self,
+        query_text: &str,
+        query_result_type: QueryResultType,
+    ) -> crate::Result<Self::Query> {
+        let result = self.connection.prepare_query(query_text, query_result_type);
+
+        // Queries that fail to prepare are marked as such, so that replaying them fails at the
+        // same point, before any parameters are bound
+        if let Err(error) = &result {
+            self.write_entry(vec![
+                encode_query(query_text, &BTreeMap::new()),
+                json!({ "prepare-error": encode_error(error) }),
+            ])?;
+        }
+
+        Ok(RecordingQuery {
+            query: result?,
+            query_text: query_text.to_owned(),
+            parameters: BTreeMap::new(),
+        })
+    }
+}
+
+type RecordedRows = (Vec<String>, Vec<Vec<OwnedValueUnion>>);
+
+fn read_rows<Row: ListFeatures<Identifier = String>>(
+    row_iterator: impl RowStream<Item = Row>,
+) -> crate::Result<RecordedRows> {
+    let column_names = row_iterator.column_names().to_vec();
+    let mut rows = Vec::new();
+
+    for row in row_iterator {
+        rows.push(
+            (0..column_names.len())
+                .map(|index| {
+                    Ok(row
+                        .take_feature_at(index)?
+                        .map(OwnedValueUnion::from)
+                        .unwrap_or(OwnedValueUnion::Null))
+                })
+                .collect::<crate::Result<Vec<_>>>()?,
+        );
+    }
+
+    Ok((column_names, rows))
+}
+
+fn encode_query(query_text: &str, parameters: &BTreeMap<String, OwnedValueUnion>) -> JsonValue {
+    let parameters = parameters
+        .iter()
+        .map(|(name, value)| (name.clone(), encode_value(value)))
+        .collect::<Map<_, _>>();
+
+    json!({ "query": query_text, "parameters": parameters })
+}
+
+// Values are written as an object naming their data type, such as `{"I64": 20}`, so that they
+// are read back as the same variant. Text is used wherever JSON has no exact equivalent.
+fn encode_value(value: &OwnedValueUnion) -> JsonValue {
+    let encoded = match value {
+        OwnedValueUnion::Null => return JsonValue::Null,
+        OwnedValueUnion::Bool(value) => json!(value),
+        OwnedValueUnion::U8(value) => json!(value),
+        OwnedValueUnion::U16(value) => json!(value),
+        OwnedValueUnion::U32(value) => json!(value),
+        OwnedValueUnion::U64(value) => json!(value),
+        OwnedValueUnion::U128(value) => json!(value.to_string()),
+        OwnedValueUnion::I8(value) => json!(value),
+        OwnedValueUnion::I16(value) => json!(value),
+        OwnedValueUnion::I32(value) => json!(value),
+        OwnedValueUnion::I64(value) => json!(value),
+        OwnedValueUnion::I128(value) => json!(value.to_string()),
+        OwnedValueUnion::F32(value) => encode_float(*value as f64),
+        OwnedValueUnion::F64(value) => encode_float(*value),
+        OwnedValueUnion::String(value) => json!(value),
+        OwnedValueUnion::Bytestring(value) => json!(value
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect::<String>()),
+        OwnedValueUnion::Date(value) => json!(value.to_string()),
+        OwnedValueUnion::DateTime(value) => json!(value.format(NAIVE_DATETIME_FORMAT).to_string()),
+        OwnedValueUnion::DateTimeUtc(value) => json!(value.to_rfc3339()),
+        OwnedValueUnion::DateTimeTz(value) => json!(value.to_rfc3339()),
+        OwnedValueUnion::Time(value) => json!(value.format(TIME_FORMAT).to_string()),
+        OwnedValueUnion::Duration(value) => json!({
+            "seconds": value.as_secs(),
+            "nanoseconds": value.subsec_nanos(),
+        }),
+        #[cfg(feature = "decimal")]
+        OwnedValueUnion::Decimal(value) => json!(value.to_string()),
+        #[cfg(feature = "uuid")]
+        OwnedValueUnion::Uuid(value) => json!(value.to_string()),
+        #[cfg(feature = "json")]
+        OwnedValueUnion::Json(value) => value.clone(),
+        OwnedValueUnion::List(values) => {
+            JsonValue::Array(values.iter().map(encode_value).collect())
+        }
+    };
+
+    json!({ format!("{:?}", value.data_type()): encoded })
+}
+
+// JSON has no NaN or infinities, so those are written as text
+fn encode_float(value: f64) -> JsonValue {
+    serde_json::Number::from_f64(value).map_or_else(|| json!(value.to_string()), JsonValue::Number)
+}
+
+fn decode_value(value: JsonValue) -> crate::Result<OwnedValueUnion> {
+    let (type_name, value) = match value {
+        JsonValue::Null => return Ok(OwnedValueUnion::Null),
+        JsonValue::Object(object) if object.len() == 1 => {
+            object.into_iter().next().ok_or_else(malformed_value)?
+        }
+        _ => return Err(malformed_value()),
+    };
+
+    let data_type = match type_name.as_str() {
+        "Bool" => DataType::Bool,
+        "U8" => DataType::U8,
+        "U16" => DataType::U16,
+        "U32" => DataType::U32,
+        "U64" => DataType::U64,
+        "U128" => DataType::U128,
+        "I8" => DataType::I8,
+        "I16" => DataType::I16,
+        "I32" => DataType::I32,
+        "I64" => DataType::I64,
+        "I128" => DataType::I128,
+        "F32" => DataType::F32,
+        "F64" => DataType::F64,
+        "String" => DataType::String,
+        "Bytestring" => DataType::Bytestring,
+        "Date" => DataType::Date,
+        "DateTime" => DataType::DateTime,
+        "DateTimeUtc" => DataType::DateTimeUtc,
+        "DateTimeTz" => DataType::DateTimeTz,
+        "Time" => DataType::Time,
+        "Duration" => DataType::Duration,
+        #[cfg(feature = "decimal")]
+        "Decimal" => DataType::Decimal,
+        #[cfg(feature = "uuid")]
+        "Uuid" => DataType::Uuid,
+        #[cfg(feature = "json")]
+        "Json" => DataType::Json,
+        "List" => DataType::List,
+        _ => return Err(invalid_recording("unknown value type")),
+    };
+
+    match (data_type, value) {
+        (DataType::Bool, JsonValue::Bool(value)) => Ok(OwnedValueUnion::Bool(value)),
+        (DataType::String, JsonValue::String(value)) => Ok(OwnedValueUnion::String(value)),
+        (DataType::Duration, value) => {
+            let (Some(seconds), Some(nanoseconds)) = (
+                value["seconds"].as_u64(),
+                value["nanoseconds"]
+                    .as_u64()
+                    .and_then(|value| u32::try_from(value).ok()),
+            ) else {
+                return Err(malformed_value());
+            };
+
+            Ok(OwnedValueUnion::Duration(std::time::Duration::new(
+                seconds,
+                nanoseconds,
+            )))
+        }
+        #[cfg(feature = "json")]
+        (DataType::Json, value) => Ok(OwnedValueUnion::Json(value)),
+        (DataType::List, JsonValue::Array(values)) => Ok(OwnedValueUnion::List(
+            values
+                .into_iter()
+                .map(decode_value)
+                .collect::<crate::Result<_>>()?,
+        )),
+        // Everything else is text or a number, which are parsed from their text
+        (data_type, JsonValue::String(text)) => {
+            OwnedValueUnion::parse(&text, &data_type).map_err(|_| malformed_value())
+        }
+        (data_type, JsonValue::Number(number)) => {
+            OwnedValueUnion::parse(&number.to_string(), &data_type).map_err(|_| malformed_value())
+        }
+        _ => Err(malformed_value()),
+    }
+}
+
+fn malformed_value() -> crate::Error {
+    invalid_recording("value is malformed")
+}
+
+// Errors that a database can report are recorded with their kind and fields, so that replaying
+// them gives back the same variant. Any other error is replayed as a `RecordedError` with its
+// message.
+fn encode_error(error: &crate::Error) -> JsonValue {
+    match error {
+        crate::Error::SqliteError { sqlite_error } => json!({
+            "kind": "sqlite",
+            "code": sqlite_error.code,
+            "message": sqlite_error.message,
+        }),
+        crate::Error::UniqueConstraintViolation {
+            constraint,
+            columns,
+            message,
+        } => json!({
+            "kind": "unique",
+            "constraint": constraint,
+            "columns": columns,
+            "message": message,
+        }),
+        crate::Error::ForeignKeyConstraintViolation {
+            constraint,
+            message,
+        } => json!({ "kind": "foreign-key", "constraint": constraint, "message": message }),
+        crate::Error::NotNullConstraintViolation { column, message } => {
+            json!({ "kind": "not-null", "column": column, "message": message })
+        }
+        crate::Error::CheckConstraintViolation {
+            constraint,
+            message,
+        } => json!({ "kind": "check", "constraint": constraint, "message": message }),
+        crate::Error::DatabaseBusy { message } => json!({ "kind": "busy", "message": message }),
+        crate::Error::DatabaseLocked { message } => {
+            json!({ "kind": "locked", "message": message })
+        }
+        crate::Error::DatabaseReadOnly { message } => {
+            json!({ "kind": "read-only", "message": message })
+        }
+        crate::Error::SchemaMismatch { object, message } => {
+            json!({ "kind": "schema-mismatch", "object": object, "message": message })
+        }
+        crate::Error::QueryCancelled => json!({ "kind": "cancelled" }),
+        crate::Error::QueryTimedOut => json!({ "kind": "timed-out" }),
+        crate::Error::InvalidQuery { message } => {
+            json!({ "kind": "invalid-query", "message": message })
+        }
+        crate::Error::RecordedError { message } => json!({ "kind": "other", "message": message }),
+        error => json!({ "kind": "other", "message": error.to_string() }),
+    }
+}
+
+fn decode_error(error: &JsonValue) -> crate::Result<crate::Error> {
+    let error = match error["kind"].as_str() {
+        Some("sqlite") => crate::Error::SqliteError {
+            sqlite_error: sqlite::Error {
+                code: match &error["code"] {
+                    JsonValue::Null => None,
+                    code => Some(
+                        code.as_i64()
+                            .and_then(|code| isize::try_from(code).ok())
+                            .ok_or_else(malformed_error)?,
+                    ),
+                },
+                message: optional_string(&error["message"])?,
+            },
+        },
+        Some("unique") => crate::Error::UniqueConstraintViolation {
+            constraint: optional_string(&error["constraint"])?,
+            columns: error["columns"]
+                .as_array()
+                .ok_or_else(malformed_error)?
+                .iter()
+                .map(required_string)
+                .collect::<crate::Result<_>>()?,
+            message: required_string(&error["message"])?,
+        },
+        Some("foreign-key") => crate::Error::ForeignKeyConstraintViolation {
+            constraint: optional_string(&error["constraint"])?,
+            message: required_string(&error["message"])?,
+        },
+        Some("not-null") => crate::Error::NotNullConstraintViolation {
+            column: optional_string(&error["column"])?,
+            message: required_string(&error["message"])?,
+        },
+        Some("check") => crate::Error::CheckConstraintViolation {
+            constraint: optional_string(&error["constraint"])?,
+            message: required_string(&error["message"])?,
+        },
+        Some("busy") => crate::Error::DatabaseBusy {
+            message: required_string(&error["message"])?,
+        },
+        Some("locked") => crate::Error::DatabaseLocked {
+            message: required_string(&error["message"])?,
+        },
+        Some("read-only") => crate::Error::DatabaseReadOnly {
+            message: required_string(&error["message"])?,
+        },
+        Some("schema-mismatch") => crate::Error::SchemaMismatch {
+            object: optional_string(&error["object"])?,
+            message: required_string(&error["message"])?,
+        },
+        Some("cancelled") => crate::Error::QueryCancelled,
+        Some("timed-out") => crate::Error::QueryTimedOut,
+        Some("invalid-query") => crate::Error::InvalidQuery {
+            message: required_string(&error["message"])?,
+        },
+        Some("other") => crate::Error::RecordedError {
+            message: required_string(&error["message"])?,
+        },
+        _ => return Err(invalid_recording("unknown error kind")),
+    };
+
+    Ok(error)
+}
+
+fn required_string(value: &JsonValue) -> crate::Result<String> {
+    value
+        .as_str()
+        .map(str::to_owned)
+        .ok_or_else(malformed_error)
+}
+
+fn optional_string(value: &JsonValue) -> crate::Result<Option<String>> {
+    match value {
+        JsonValue::Null => Ok(None),
+        value => required_string(value).map(Some),
+    }
+}
+
+fn malformed_error() -> crate::Error {
+    invalid_recording("error entry has malformed fields")
+}
+
+fn dialect_name(dialect: Dialect) -> &'static str {
+    match dialect {
+        Dialect::Sqlite => "sqlite",
+        Dialect::Postgres => "postgres",
+        Dialect::MySql => "mysql",
+    }
+}
+
+fn dialect_from_name(name: &str) -> Option<Dialect> {
+    match name {
+        "sqlite" => Some(Dialect::Sqlite),
+        "postgres" => Some(Dialect::Postgres),
+        "mysql" => Some(Dialect::MySql),
+        _ => None,
+    }
+}
+
+pub(super) fn read_recording<P: AsRef<Path>>(
+    path: P,
+) -> crate::Result<(Dialect, Vec<MockExpectation>)> {
+    let contents = fs::read_to_string(path)?;
+    let mut lines = contents.lines();
+
+    let dialect = lines
+        .next()
+        .and_then(|header| header.strip_prefix(RECORDING_HEADER)?.strip_prefix(' '))
+        .and_then(dialect_from_name)
+        .ok_or_else(|| invalid_recording("missing or unsupported header"))?;
+
+    let mut expectations = Vec::<MockExpectation>::new();
+
+    for line in lines {
+        let entry = serde_json::from_str::<JsonValue>(line)
+            .map_err(|error| invalid_recording(&error.to_string()))?;
+
+        if let Some(query_text) = entry.get("query") {
+            let query_text = required_string(query_text)
+                .map_err(|_| invalid_recording("query entry is missing its text"))?;
+
+            let parameters = entry["parameters"]
+                .as_object()
+                .ok_or_else(|| invalid_recording("query entry has malformed parameters"))?
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), decode_value(value.clone())?)))
+                .collect::<crate::Result<_>>()?;
+
+            expectations.push(MockExpectation {
+                query_text,
+                parameters,
+                result: MockResult::None,
+            });
+        } else if let Some(count) = entry.get("count") {
+            let count = count
+                .as_u64()
+                .and_then(|count| usize::try_from(count).ok())
+                .ok_or_else(|| invalid_recording("change count is not a number"))?;
+
+            current_expectation(&mut expectations)?.result = MockResult::ChangeCount(count);
+        } else if let Some(column_names) = entry.get("columns") {
+            let column_names = column_names
+                .as_array()
+                .ok_or_else(|| invalid_recording("column names are not a list"))?
+                .iter()
+                .map(|column_name| {
+                    required_string(column_name)
+                        .map_err(|_| invalid_recording("column name is not a string"))
+                })
+                .collect::<crate::Result<Vec<_>>>()?;
+
+            current_expectation(&mut expectations)?.result = MockResult::Rows {
+                column_names: column_names.into(),
+                rows: Vec::new(),
+            };
+        } else if let Some(values) = entry.get("row") {
+            let MockResult::Rows { rows, .. } = &mut current_expectation(&mut expectations)?.result
+            else {
+                return Err(invalid_recording(
+                    "row entry does not follow a columns entry",
+                ));
+            };
+
+            rows.push(
+                values
+                    .as_array()
+                    .ok_or_else(|| invalid_recording("row is not a list"))?
+                    .iter()
+                    .map(|value| decode_value(value.clone()))
+                    .collect::<crate::Result<_>>()?,
+            );
+        } else if let Some(error) = entry.get("error") {
+            current_expectation(&mut expectations)?.result =
+                MockResult::Error(decode_error(error)?);
+        } else if let Some(error) = entry.get("prepare-error") {
+            current_expectation(&mut expectations)?.result =
+                MockResult::PrepareError(decode_error(error)?);
+        } else {
+            return Err(invalid_recording("unknown entry kind"));
+        }
+    }
+
+    Ok((dialect, expectations))
+}
+
+fn current_expectation(
+    expectations: &mut [MockExpectation],
+) -> crate::Result<&mut MockExpectation> {
+    expectations
+        .last_mut()
+        .ok_or_else(|| invalid_recording("result entry does not follow a query entry"))
+}
+
+fn invalid_recording(message: &str) -> crate::Error {
+    crate::Error::InvalidRecording {
+        message: message.to_owned(),
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RetryMetrics {
+    pub retried_operations: u64,
+    pub retries: u64,
+    pub exhausted_operations: u64,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct RetryCounters {
+    retried_operations: AtomicU64,
+    retries: AtomicU64,
+    exhausted_operations: AtomicU64,
+}
+
+impl RetryCounters {
+    pub(super) fn record(&self, retries: u32, exhausted: bool) {
+        if retries > 0 {
+            self.retried_operations.fetch_add(1, Ordering::Relaxed);
+            self.retries.fetch_add(retries as u64, Ordering::Relaxed);
+        }
+
+        if exhausted {
+            self.exhausted_operations.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(super) fn snapshot(&self) -> RetryMetrics {
+        RetryMetrics {
+            retried_operations: self.retried_operations.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            exhausted_operations: self.exhausted_operations.load(Ordering::Relaxed),
+        }
+    }
+}
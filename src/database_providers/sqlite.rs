@@ -15,13 +15,23 @@
 
 use lazy_static::lazy_static;
 use regex::Regex;
-use sqlite::{Connection, Statement};
-use std::{marker::PhantomData, ops::Index};
+use sqlite::{Connection, ConnectionWithFullMutex, ParameterIndex, State, Statement};
+use std::{ops::Index, path::Path, sync::Arc, thread, time::Duration};
 
+#[cfg(feature = "uuid")]
+use super::UuidStorage;
+use super::{
+    retry_metrics::RetryCounters,
+    sqlite_exclusive_access::{ExclusiveAccess, ExclusiveAccessGuard},
+    sqlite_interrupt::{install_progress_handler, ExecutionControl},
+    IntegerOverflowPolicy, RetryMetrics, RetryPolicy,
+};
 use crate::{
-    domain::ValueUnion,
+    domain::{duration_to_millis, DataType, ValueUnion},
+    query_building::Dialect,
     query_execution::{
-        ExecuteQuery, GetQueryResultType, InjectFeatures, QueryResultType, TakeFeatures,
+        CancelHandle, ExecuteQuery, GetDialect, GetQueryResultType, InjectFeatures, ListFeatures,
+        PrepareQuery, QueryResultType, RowStream, TakeFeatures,
     },
 };
 
@@ -29,111 +39,574 @@ const DEFAULT_TRUE_STRING: &str = "true";
 const DEFAULT_FALSE_STRING: &str = "false";
 const DEFAULT_DATE_FORMAT: &str = "%F";
 const DEFAULT_DATETIME_FORMAT: &str = "%+";
+const DEFAULT_NAIVE_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const DEFAULT_TIME_FORMAT: &str = "%H:%M:%S%.3f";
 
 lazy_static! {
     static ref IDENTIFIER_REGEX: Regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
 }
 
-pub struct SqliteConnection<'connection> {
-    sqlite_connection: Connection,
+struct SqliteHandle {
+    sqlite_connection: ConnectionWithFullMutex,
+    retry_counters: RetryCounters,
+    exclusive_access: ExclusiveAccess,
+}
+
+#[derive(Clone)]
+struct SqliteSettings {
     true_string: String,
     false_string: String,
     date_format: String,
     datetime_format: String,
-    phantom: PhantomData<&'connection ()>,
+    naive_datetime_format: String,
+    time_format: String,
+    retry_policy: RetryPolicy,
+    integer_overflow_policy: IntegerOverflowPolicy,
+    #[cfg(feature = "uuid")]
+    uuid_storage: UuidStorage,
 }
 
-pub struct SqliteQuery<'connection> {
-    connection: &'connection SqliteConnection<'connection>,
-    statement: Statement<'connection>,
+#[derive(Clone)]
+pub struct SqliteConnection {
+    handle: Arc<SqliteHandle>,
+    settings: Arc<SqliteSettings>,
+}
+
+pub struct SqliteQuery {
+    // The statement borrows from the handle owned by `connection`, so it must be declared first in
+    // order to be finalized before the handle can be dropped.
+    statement: Statement<'static>,
+    connection: SqliteConnection,
     result_type: QueryResultType,
+    timeout: Option<Duration>,
+    cancel_handle: CancelHandle,
+}
+
+// SAFETY: `Statement` is only `!Send` because of its `Rc` column mapping and the raw handle it
+// points to. The handle is opened in serialized mode, so SQLite guards every call on it with its own
+// mutex, and the column mapping is never cloned out of the statement (rows are converted into
+// `SqliteRow` before being handed out), so no other thread can be holding a reference to it.
+unsafe impl Send for SqliteQuery {}
+
+pub struct SqliteRow {
+    column_names: Arc<[String]>,
+    values: Vec<sqlite::Value>,
+}
+
+pub struct SqliteRowIterator<'query> {
+    statement: &'query mut Statement<'static>,
+    column_names: Arc<[String]>,
+    connection: SqliteConnection,
+    control: ExecutionControl,
+    finished: bool,
 }
 
-impl<'connection> GetQueryResultType for SqliteQuery<'connection> {
+// Owns its query so that it can be kept open across calls, like the streams of the async connection
+pub(super) struct OwnedSqliteRowIterator {
+    query: SqliteQuery,
+    column_names: Arc<[String]>,
+    control: ExecutionControl,
+    finished: bool,
+}
+
+impl GetQueryResultType for SqliteQuery {
     fn query_result_type(&self) -> QueryResultType {
         self.result_type
     }
 }
 
-impl<'connection> SqliteConnection<'connection> {
+impl SqliteConnection {
     pub fn connect_memory() -> crate::Result<Self> {
+        Self::connect_path(":memory:")
+    }
+
+    pub fn connect_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        Self::connect_path(path)
+    }
+
+    pub(super) fn connect_path<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        // SAFETY: Only reads a compile-time constant of the linked SQLite library
+        if unsafe { sqlite3_sys::sqlite3_threadsafe() } == 0 {
+            return Err(crate::Error::SqliteNotThreadSafe);
+        }
+
+        // Serialized mode makes SQLite guard every call on the handle with its own mutex, which is
+        // what lets clones of the connection be used from several threads at once
+        let sqlite_connection = Connection::open_with_full_mutex(path)?;
+
+        // SAFETY: The raw handle is valid for as long as `sqlite_connection` is alive. Extended
+        // result codes are needed to tell the different kinds of constraint violations apart.
+        unsafe {
+            sqlite3_sys::sqlite3_extended_result_codes(sqlite_connection.as_raw(), 1);
+        }
+
+        install_progress_handler(&sqlite_connection);
+
         Ok(Self {
-            sqlite_connection: sqlite::open(":memory:")?,
-            true_string: DEFAULT_TRUE_STRING.to_owned(),
-            false_string: DEFAULT_FALSE_STRING.to_owned(),
-            date_format: DEFAULT_DATE_FORMAT.to_owned(),
-            datetime_format: DEFAULT_DATETIME_FORMAT.to_owned(),
-            phantom: PhantomData,
+            handle: Arc::new(SqliteHandle {
+                sqlite_connection,
+                retry_counters: RetryCounters::default(),
+                exclusive_access: ExclusiveAccess::default(),
+            }),
+            settings: Arc::new(SqliteSettings {
+                true_string: DEFAULT_TRUE_STRING.to_owned(),
+                false_string: DEFAULT_FALSE_STRING.to_owned(),
+                date_format: DEFAULT_DATE_FORMAT.to_owned(),
+                datetime_format: DEFAULT_DATETIME_FORMAT.to_owned(),
+                naive_datetime_format: DEFAULT_NAIVE_DATETIME_FORMAT.to_owned(),
+                time_format: DEFAULT_TIME_FORMAT.to_owned(),
+                retry_policy: RetryPolicy::disabled(),
+                integer_overflow_policy: IntegerOverflowPolicy::default(),
+                #[cfg(feature = "uuid")]
+                uuid_storage: UuidStorage::default(),
+            }),
         })
     }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.settings.retry_policy
+    }
+
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        Arc::make_mut(&mut self.settings).retry_policy = retry_policy;
+    }
+
+    pub fn integer_overflow_policy(&self) -> IntegerOverflowPolicy {
+        self.settings.integer_overflow_policy
+    }
+
+    pub fn set_integer_overflow_policy(&mut self, integer_overflow_policy: IntegerOverflowPolicy) {
+        Arc::make_mut(&mut self.settings).integer_overflow_policy = integer_overflow_policy;
+    }
+
+    #[cfg(feature = "uuid")]
+    pub fn uuid_storage(&self) -> UuidStorage {
+        self.settings.uuid_storage
+    }
+
+    #[cfg(feature = "uuid")]
+    pub fn set_uuid_storage(&mut self, uuid_storage: UuidStorage) {
+        Arc::make_mut(&mut self.settings).uuid_storage = uuid_storage;
+    }
+
+    pub fn parameter_limit(&self) -> usize {
+        // SAFETY: The raw handle is valid for as long as the connection is alive, and a negative new
+        // value only queries the current limit.
+        let limit = unsafe {
+            sqlite3_sys::sqlite3_limit(
+                self.handle.sqlite_connection.as_raw(),
+                sqlite3_sys::SQLITE_LIMIT_VARIABLE_NUMBER,
+                -1,
+            )
+        };
+
+        limit.max(0) as usize
+    }
+
+    // Columns that allow null have nullable data types
+    pub fn table_columns(&self, table: &str) -> crate::Result<Vec<(String, DataType)>> {
+        let mut query = SqliteQuery::new_with_iterator(
+            self,
+            "SELECT name, type, \"notnull\" FROM pragma_table_info(:table)",
+        )?;
+
+        query.inject_feature(&"table".to_owned(), &ValueUnion::String(&table.to_owned()))?;
+
+        let columns = self
+            .execute_with_iterator(&mut query)?
+            .map(|row| {
+                let name = row.take_sql_value::<String>(&"name".to_owned())?;
+                let data_type = Dialect::Sqlite
+                    .data_type(&row.take_sql_value::<String>(&"type".to_owned())?)?;
+
+                Ok(if row.take_sql_value::<bool>(&"notnull".to_owned())? {
+                    (name, data_type)
+                } else {
+                    (name, data_type.nullable())
+                })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        if columns.is_empty() {
+            return Err(crate::Error::SchemaMismatch {
+                object: Some(table.to_owned()),
+                message: format!("no such table: {table}"),
+            });
+        }
+
+        Ok(columns)
+    }
+
+    pub fn retry_metrics(&self) -> RetryMetrics {
+        self.handle.retry_counters.snapshot()
+    }
+
+    pub(super) fn exclusive_access(&self) -> ExclusiveAccessGuard<'_> {
+        self.handle.exclusive_access.acquire()
+    }
+
+    fn retry_while_busy<Value>(
+        &self,
+        mut operation: impl FnMut() -> Result<Value, sqlite::Error>,
+    ) -> Result<Value, sqlite::Error> {
+        // Every statement goes through here, so this is where other threads wait while one thread
+        // has exclusive access to the handle, and where that thread waits for statements that are
+        // already running
+        let _shared_access = self.handle.exclusive_access.share();
+
+        let retry_policy = &self.settings.retry_policy;
+        let mut retries = 0;
+
+        loop {
+            match operation() {
+                Err(error) if is_busy(&error) && retry_policy.allows_retry(retries) => {
+                    thread::sleep(retry_policy.backoff(retries));
+                    retries += 1;
+                }
+                result => {
+                    let exhausted = retries > 0 && matches!(&result, Err(error) if is_busy(error));
+                    self.handle.retry_counters.record(retries, exhausted);
+                    return result;
+                }
+            }
+        }
+    }
+
+    // Runs a query that has no rows to read
+    fn step_once(&self, query: &mut SqliteQuery) -> crate::Result<()> {
+        let control = ExecutionControl::new(query.cancel_handle.clone(), query.timeout);
+        let result = self.retry_while_busy(|| control.run(|| query.statement.next()));
+        query.cancel_handle.clear();
+
+        if result.is_err() {
+            // SQLite reports a failed step again when the statement is next reset, so resetting
+            // here keeps that error from coming back out of `SqliteQuery::reset`
+            let _ = query.statement.reset();
+        }
+
+        result?;
+
+        Ok(())
+    }
 }
 
-impl<'connection> SqliteQuery<'connection> {
-    fn new_with_result_type(
-        connection: &'connection SqliteConnection,
+fn is_busy(error: &sqlite::Error) -> bool {
+    error
+        .code
+        .is_some_and(|code| code as i32 & 0xff == sqlite3_sys::SQLITE_BUSY)
+}
+
+impl SqliteQuery {
+    pub(super) fn new_with_result_type(
+        connection: &SqliteConnection,
         query_text: &str,
         result_type: QueryResultType,
     ) -> crate::Result<Self> {
+        // Preparing reads the schema, so it can run into a locked database just like executing
+        let statement = connection
+            .retry_while_busy(|| connection.handle.sqlite_connection.prepare(query_text))?;
+
         Ok(Self {
-            connection,
-            statement: connection.sqlite_connection.prepare(query_text)?,
+            // SAFETY: The statement borrows the connection inside `connection.handle`, which lives
+            // in an `Arc` allocation that never moves. The query holds a clone of that `Arc`, and
+            // the statement field is declared before it, so the connection outlives the statement.
+            // The `'static` lifetime never leaves the query, since the field is private.
+            statement: unsafe {
+                std::mem::transmute::<Statement<'_>, Statement<'static>>(statement)
+            },
+            connection: connection.clone(),
             result_type,
+            timeout: None,
+            cancel_handle: CancelHandle::new(),
         })
     }
 
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    // Cancels executions without results or with a change count, which can be cancelled from
+    // another thread while they run. Row iterators have a cancel handle of their own. A
+    // cancellation only applies to the execution that is running, or the next one to start, and
+    // is cleared when the query is reset.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    pub fn reset(&mut self) -> crate::Result<()> {
+        self.statement.reset()?;
+        self.cancel_handle.clear();
+
+        Ok(())
+    }
+
+    pub(super) fn bind_value<Index: ParameterIndex>(
+        &mut self,
+        binding_index: Index,
+        value: &ValueUnion,
+    ) -> crate::Result<()> {
+        let value = self.sqlite_value(value)?;
+
+        self.statement.bind((binding_index, value))?;
+
+        Ok(())
+    }
+
+    fn sqlite_value(&self, value: &ValueUnion) -> crate::Result<sqlite::Value> {
+        let settings = &self.connection.settings;
+
+        let value = match value {
+            ValueUnion::Null => sqlite::Value::Null,
+            ValueUnion::Bool(value) => sqlite::Value::String(if *value {
+                settings.true_string.clone()
+            } else {
+                settings.false_string.clone()
+            }),
+            ValueUnion::U8(value) => sqlite::Value::Integer(*value as i64),
+            ValueUnion::U16(value) => sqlite::Value::Integer(*value as i64),
+            ValueUnion::U32(value) => sqlite::Value::Integer(*value as i64),
+            ValueUnion::U64(value) => match i64::try_from(*value) {
+                Ok(value) => sqlite::Value::Integer(value),
+                Err(_) => self.out_of_range_value(value.to_string(), &value.to_be_bytes())?,
+            },
+            ValueUnion::U128(value) => match i64::try_from(*value) {
+                Ok(value) => sqlite::Value::Integer(value),
+                Err(_) => self.out_of_range_value(value.to_string(), &value.to_be_bytes())?,
+            },
+            ValueUnion::I8(value) => sqlite::Value::Integer(*value as i64),
+            ValueUnion::I16(value) => sqlite::Value::Integer(*value as i64),
+            ValueUnion::I32(value) => sqlite::Value::Integer(*value as i64),
+            ValueUnion::I64(value) => sqlite::Value::Integer(*value),
+            ValueUnion::I128(value) => match i64::try_from(*value) {
+                Ok(value) => sqlite::Value::Integer(value),
+                Err(_) => self.out_of_range_value(value.to_string(), &value.to_be_bytes())?,
+            },
+            ValueUnion::F32(value) => sqlite::Value::Float(*value as f64),
+            ValueUnion::F64(value) => sqlite::Value::Float(*value),
+            ValueUnion::String(value) => sqlite::Value::String((*value).clone()),
+            ValueUnion::Bytestring(value) => sqlite::Value::Binary(value.to_vec()),
+            ValueUnion::Date(value) => {
+                sqlite::Value::String(value.format(settings.date_format.as_str()).to_string())
+            }
+            // Naive datetimes have no offset, so they can't use the RFC 3339 format
+            ValueUnion::DateTime(value) => sqlite::Value::String(
+                value
+                    .format(settings.naive_datetime_format.as_str())
+                    .to_string(),
+            ),
+            ValueUnion::DateTimeUtc(value) => {
+                sqlite::Value::String(value.format(settings.datetime_format.as_str()).to_string())
+            }
+            ValueUnion::DateTimeTz(value) => {
+                sqlite::Value::String(value.format(settings.datetime_format.as_str()).to_string())
+            }
+            ValueUnion::Time(value) => {
+                sqlite::Value::String(value.format(settings.time_format.as_str()).to_string())
+            }
+            ValueUnion::Duration(value) => sqlite::Value::Integer(duration_to_millis(value)),
+            // Stored as text because SQLite has no exact numeric type
+            #[cfg(feature = "decimal")]
+            ValueUnion::Decimal(value) => sqlite::Value::String(value.to_string()),
+            #[cfg(feature = "uuid")]
+            ValueUnion::Uuid(value) => match settings.uuid_storage {
+                UuidStorage::Text => sqlite::Value::String(value.hyphenated().to_string()),
+                UuidStorage::Blob => sqlite::Value::Binary(value.as_bytes().to_vec()),
+            },
+            #[cfg(feature = "json")]
+            ValueUnion::Json(value) => sqlite::Value::String(value.to_string()),
+            // SQLite has no arrays, so lists are bound as JSON arrays for use with json_each
+            ValueUnion::List(values) => sqlite::Value::String(encode_json_array(
+                &values
+                    .iter()
+                    .map(|value| self.sqlite_value(&value.as_value_union()))
+                    .collect::<crate::Result<Vec<_>>>()?,
+            )?),
+        };
+
+        Ok(value)
+    }
+
+    fn out_of_range_value(&self, text: String, bytes: &[u8]) -> crate::Result<sqlite::Value> {
+        match self.connection.settings.integer_overflow_policy {
+            IntegerOverflowPolicy::Error => Err(crate::Error::IntegerOutOfRange { value: text }),
+            IntegerOverflowPolicy::Text => Ok(sqlite::Value::String(text)),
+            IntegerOverflowPolicy::Blob => Ok(sqlite::Value::Binary(bytes.to_vec())),
+        }
+    }
+
     pub fn new_without_results(
-        connection: &'connection SqliteConnection,
+        connection: &SqliteConnection,
         query_text: &str,
     ) -> crate::Result<Self> {
         Self::new_with_result_type(connection, query_text, QueryResultType::None)
     }
 
     pub fn new_with_change_count(
-        connection: &'connection SqliteConnection,
+        connection: &SqliteConnection,
         query_text: &str,
     ) -> crate::Result<Self> {
         Self::new_with_result_type(connection, query_text, QueryResultType::ChangeCount)
     }
 
     pub fn new_with_iterator(
-        connection: &'connection SqliteConnection,
+        connection: &SqliteConnection,
         query_text: &str,
     ) -> crate::Result<Self> {
         Self::new_with_result_type(connection, query_text, QueryResultType::Iterator)
     }
 }
 
-impl<'connection> ExecuteQuery for SqliteConnection<'connection> {
-    type Query = SqliteQuery<'connection>;
-    type Row = Result<sqlite::Row, sqlite::Error>;
-    type RowIterator<'query>
-    = sqlite::Cursor<'connection, 'query>
-    where
-        'connection: 'query;
+impl GetDialect for SqliteConnection {
+    fn dialect(&self) -> Dialect {
+        Dialect::Sqlite
+    }
+}
+
+impl ExecuteQuery for SqliteConnection {
+    type Query = SqliteQuery;
+    type Row = Result<SqliteRow, sqlite::Error>;
+    type RowIterator<'query> = SqliteRowIterator<'query>;
 
     fn execute_without_results(&self, query: &mut Self::Query) -> crate::Result<()> {
-        query.statement.next()?;
+        self.step_once(query)?;
 
         Ok(())
     }
 
     fn execute_with_change_count(&self, query: &mut Self::Query) -> crate::Result<usize> {
-        query.statement.next()?;
+        self.step_once(query)?;
 
-        Ok(self.sqlite_connection.change_count())
+        Ok(query.connection.handle.sqlite_connection.change_count())
     }
 
     fn execute_with_iterator<'query>(
         &self,
         query: &'query mut Self::Query,
     ) -> crate::Result<Self::RowIterator<'query>> {
-        println!("columns: {:?}", query.statement.column_names());
+        let column_names = query.statement.column_names().into();
+
+        Ok(SqliteRowIterator {
+            control: ExecutionControl::new(CancelHandle::new(), query.timeout),
+            statement: &mut query.statement,
+            column_names,
+            connection: self.clone(),
+            finished: false,
+        })
+    }
+}
+
+impl PrepareQuery for SqliteConnection {
+    fn prepare_query(
+        &self,
+        query_text: &str,
+        query_result_type: QueryResultType,
+    ) -> crate::Result<Self::Query> {
+        SqliteQuery::new_with_result_type(self, query_text, query_result_type)
+    }
+}
+
+impl<'query> Iterator for SqliteRowIterator<'query> {
+    type Item = Result<SqliteRow, sqlite::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_row(
+            &self.connection,
+            self.statement,
+            &self.column_names,
+            &self.control,
+            &mut self.finished,
+        )
+    }
+}
+
+impl<'query> RowStream for SqliteRowIterator<'query> {
+    fn cancel_handle(&self) -> CancelHandle {
+        self.control.cancel_handle().clone()
+    }
+
+    fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+}
+
+impl<'query> Drop for SqliteRowIterator<'query> {
+    fn drop(&mut self) {
+        // Resetting releases the statement's read locks right away instead of when the query is
+        // finally dropped, and lets the query be executed again from the start
+        let _ = self.statement.reset();
+    }
+}
+
+impl SqliteQuery {
+    pub(super) fn into_row_iterator(self) -> OwnedSqliteRowIterator {
+        OwnedSqliteRowIterator {
+            column_names: self.statement.column_names().into(),
+            control: ExecutionControl::new(CancelHandle::new(), self.timeout),
+            query: self,
+            finished: false,
+        }
+    }
+}
+
+impl Iterator for OwnedSqliteRowIterator {
+    type Item = Result<SqliteRow, sqlite::Error>;
 
-        Ok(query.statement.iter())
+    fn next(&mut self) -> Option<Self::Item> {
+        next_row(
+            &self.query.connection,
+            &mut self.query.statement,
+            &self.column_names,
+            &self.control,
+            &mut self.finished,
+        )
     }
 }
 
-impl<'connection> InjectFeatures for SqliteQuery<'connection> {
+impl OwnedSqliteRowIterator {
+    pub(super) fn cancel_handle(&self) -> CancelHandle {
+        self.control.cancel_handle().clone()
+    }
+}
+
+fn next_row(
+    connection: &SqliteConnection,
+    statement: &mut Statement<'static>,
+    column_names: &Arc<[String]>,
+    control: &ExecutionControl,
+    finished: &mut bool,
+) -> Option<Result<SqliteRow, sqlite::Error>> {
+    if *finished {
+        return None;
+    }
+
+    let row = match connection.retry_while_busy(|| control.run(|| statement.next())) {
+        Ok(State::Row) => (0..column_names.len())
+            .map(|index| statement.read::<sqlite::Value, _>(index))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|values| SqliteRow {
+                column_names: column_names.clone(),
+                values,
+            }),
+        Ok(State::Done) => {
+            *finished = true;
+            return None;
+        }
+        Err(error) => Err(error),
+    };
+
+    // `retry_while_busy` has already stepped again after any BUSY errors it was allowed to retry,
+    // which resumes the statement. Stepping again after any other error would start the statement
+    // over from the beginning.
+    *finished = row.is_err();
+
+    Some(row)
+}
+
+impl InjectFeatures for SqliteQuery {
     type Identifier = String;
 
     fn inject_feature(
@@ -141,99 +614,171 @@ impl<'connection> InjectFeatures for SqliteQuery<'connection> {
         identifier: &Self::Identifier,
         value: &ValueUnion,
     ) -> crate::Result<()> {
-        if !IDENTIFIER_REGEX.is_match(identifier) {
-            return Err(crate::Error::InvalidFeatureName {
-                feature_name: identifier.clone(),
-            });
-        }
+        validate_identifier(identifier)?;
 
-        let binding_index = format!(":{}", identifier);
+        let parameter = format!(":{}", identifier);
 
-        match value {
-            ValueUnion::Bool(value) => {
-                if *value {
-                    self.statement
-                        .bind((binding_index.as_str(), self.connection.true_string.as_str()))
-                } else {
-                    self.statement.bind((
-                        binding_index.as_str(),
-                        self.connection.false_string.as_str(),
-                    ))
-                }
-            }
-            ValueUnion::U8(value) => self.statement.bind((binding_index.as_str(), *value as i64)),
-            ValueUnion::U16(value) => self.statement.bind((binding_index.as_str(), *value as i64)),
-            ValueUnion::U32(value) => self.statement.bind((binding_index.as_str(), *value as i64)),
-            ValueUnion::U64(value) => {
-                if let Ok(value) = i64::try_from(*value) {
-                    self.statement.bind((binding_index.as_str(), value))
-                } else {
-                    self.statement
-                        .bind((binding_index.as_str(), value.to_string().as_str()))
+        // Lists are bound element by element when the query text was expanded with
+        // `expand_list_parameter`, and as a whole otherwise
+        if let ValueUnion::List(values) = value {
+            if self.statement.parameter_index(&parameter)?.is_none() {
+                for (index, value) in values.iter().enumerate() {
+                    self.bind_value(
+                        format!("{}_{}", parameter, index).as_str(),
+                        &value.as_value_union(),
+                    )?;
                 }
+
+                return Ok(());
             }
-            ValueUnion::I8(value) => self.statement.bind((binding_index.as_str(), *value as i64)),
-            ValueUnion::I16(value) => self.statement.bind((binding_index.as_str(), *value as i64)),
-            ValueUnion::I32(value) => self.statement.bind((binding_index.as_str(), *value as i64)),
-            ValueUnion::I64(value) => self.statement.bind((binding_index.as_str(), *value)),
-            ValueUnion::F32(value) => self.statement.bind((binding_index.as_str(), *value as f64)),
-            ValueUnion::F64(value) => self.statement.bind((binding_index.as_str(), *value)),
-            ValueUnion::String(value) => self
-                .statement
-                .bind((binding_index.as_str(), value.as_str())),
-            ValueUnion::Bytestring(value) => self.statement.bind((binding_index.as_str(), *value)),
-            ValueUnion::Date(value) => self.statement.bind((
-                binding_index.as_str(),
-                value
-                    .format(self.connection.date_format.as_str())
-                    .to_string()
-                    .as_str(),
-            )),
-            ValueUnion::DateTime(value) => self.statement.bind((
-                binding_index.as_str(),
-                value
-                    .format(self.connection.datetime_format.as_str())
-                    .to_string()
-                    .as_str(),
-            )),
-        }?;
+        }
 
-        Ok(())
+        self.bind_value(parameter.as_str(), value)
     }
 }
 
-fn take_feature_from_row<'value>(
-    row: &'value sqlite::Row,
-    identifier: &str,
-) -> crate::Result<Option<ValueUnion<'value>>> {
+pub(super) fn validate_identifier(identifier: &str) -> crate::Result<()> {
     if !IDENTIFIER_REGEX.is_match(identifier) {
         return Err(crate::Error::InvalidFeatureName {
             feature_name: identifier.to_owned(),
         });
     }
 
-    match row.index(identifier) {
-        sqlite::Value::Binary(value) => Ok(Some(ValueUnion::Bytestring(value))),
-        sqlite::Value::Float(value) => Ok(Some(ValueUnion::F64(*value))),
-        sqlite::Value::Integer(value) => Ok(Some(ValueUnion::I64(*value))),
-        sqlite::Value::String(value) => Ok(Some(ValueUnion::String(value))),
-        sqlite::Value::Null => Ok(None),
+    Ok(())
+}
+
+fn encode_json_array(values: &[sqlite::Value]) -> crate::Result<String> {
+    let elements = values
+        .iter()
+        .map(|value| match value {
+            sqlite::Value::Null => Ok("null".to_owned()),
+            sqlite::Value::Integer(value) => Ok(value.to_string()),
+            sqlite::Value::Float(value) if value.is_finite() => Ok(format!("{value:?}")),
+            sqlite::Value::String(value) => Ok(encode_json_string(value)),
+            sqlite::Value::Float(_) | sqlite::Value::Binary(_) => Err(crate::Error::InvalidQuery {
+                message: format!("{value:?} cannot be bound as part of a list"),
+            }),
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    Ok(format!("[{}]", elements.join(",")))
+}
+
+fn encode_json_string(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() + 2);
+
+    encoded.push('"');
+
+    for character in value.chars() {
+        match character {
+            '"' => encoded.push_str("\\\""),
+            '\\' => encoded.push_str("\\\\"),
+            character if character.is_control() => {
+                encoded.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => encoded.push(character),
+        }
+    }
+
+    encoded.push('"');
+
+    encoded
+}
+
+fn value_union_from_sqlite_value(value: &sqlite::Value) -> Option<ValueUnion<'_>> {
+    match value {
+        sqlite::Value::Binary(value) => Some(ValueUnion::Bytestring(value)),
+        sqlite::Value::Float(value) => Some(ValueUnion::F64(*value)),
+        sqlite::Value::Integer(value) => Some(ValueUnion::I64(*value)),
+        sqlite::Value::String(value) => Some(ValueUnion::String(value)),
+        sqlite::Value::Null => None,
     }
 }
 
+// sqlite::Error does not implement Clone, so we have to manually clone it
+fn clone_sqlite_error(error: &sqlite::Error) -> crate::Error {
+    crate::Error::from(sqlite::Error {
+        code: error.code,
+        message: error.message.clone(),
+    })
+}
+
+fn take_feature_from_row<'value>(
+    row: &'value sqlite::Row,
+    identifier: &str,
+) -> crate::Result<Option<ValueUnion<'value>>> {
+    validate_identifier(identifier)?;
+
+    Ok(value_union_from_sqlite_value(row.index(identifier)))
+}
+
 impl TakeFeatures for Result<sqlite::Row, sqlite::Error> {
     type Identifier = String;
 
-    fn take_feature(&self, identifier: &Self::Identifier) -> crate::Result<Option<ValueUnion>> {
+    fn take_feature(&self, identifier: &Self::Identifier) -> crate::Result<Option<ValueUnion<'_>>> {
         match self {
             Ok(row) => take_feature_from_row(row, identifier),
-            // sqlite::Error does not implement Clone, so we have to manually clone it
-            Err(error) => Err(crate::Error::SqliteError {
-                sqlite_error: sqlite::Error {
-                    code: error.code,
-                    message: error.message.clone(),
-                },
-            }),
+            Err(error) => Err(clone_sqlite_error(error)),
+        }
+    }
+}
+
+impl SqliteRow {
+    pub fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+
+    fn take_feature(&self, identifier: &str) -> crate::Result<Option<ValueUnion<'_>>> {
+        // Columns of arbitrary queries can have names like `COUNT(*)`, so names are only validated
+        // once they turn out not to match any column
+        let Some(index) = self
+            .column_names
+            .iter()
+            .position(|column_name| column_name == identifier)
+        else {
+            validate_identifier(identifier)?;
+
+            return Err(crate::Error::FeatureNotFound {
+                feature_name: identifier.to_owned(),
+            });
+        };
+
+        Ok(value_union_from_sqlite_value(&self.values[index]))
+    }
+
+    fn take_feature_at(&self, index: usize) -> crate::Result<Option<ValueUnion<'_>>> {
+        self.values
+            .get(index)
+            .map(value_union_from_sqlite_value)
+            .ok_or_else(|| crate::Error::FeatureNotFound {
+                feature_name: index.to_string(),
+            })
+    }
+}
+
+impl TakeFeatures for Result<SqliteRow, sqlite::Error> {
+    type Identifier = String;
+
+    fn take_feature(&self, identifier: &Self::Identifier) -> crate::Result<Option<ValueUnion<'_>>> {
+        match self {
+            Ok(row) => row.take_feature(identifier),
+            Err(error) => Err(clone_sqlite_error(error)),
+        }
+    }
+}
+
+impl ListFeatures for Result<SqliteRow, sqlite::Error> {
+    fn feature_identifiers(&self) -> crate::Result<Vec<Self::Identifier>> {
+        match self {
+            Ok(row) => Ok(row.column_names.to_vec()),
+            Err(error) => Err(clone_sqlite_error(error)),
+        }
+    }
+
+    fn take_feature_at(&self, index: usize) -> crate::Result<Option<ValueUnion<'_>>> {
+        match self {
+            Ok(row) => row.take_feature_at(index),
+            Err(error) => Err(clone_sqlite_error(error)),
         }
     }
 }
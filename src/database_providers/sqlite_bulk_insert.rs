@@ -0,0 +1,175 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    domain::ValueUnion,
+    query_execution::{BulkInsert, ExecuteQuery},
+};
+
+use super::{SqliteConnection, SqliteQuery};
+
+// A savepoint rather than a transaction so that bulk inserts can be nested in a transaction that
+// the caller has already started
+const SAVEPOINT_NAME: &str = "bedrock_orm_bulk_insert";
+
+impl BulkInsert for SqliteConnection {
+    fn bulk_insert<'value, Rows, Row>(
+        &self,
+        table: &str,
+        columns: &[&str],
+        rows: Rows,
+    ) -> crate::Result<usize>
+    where
+        Rows: IntoIterator<Item = Row>,
+        Row: AsRef<[ValueUnion<'value>]>,
+    {
+        if columns.is_empty() {
+            return Err(crate::Error::InvalidBulkInsert {
+                message: "at least one column is required".to_owned(),
+            });
+        }
+
+        let parameter_limit = self.parameter_limit();
+
+        if columns.len() > parameter_limit {
+            return Err(crate::Error::InvalidBulkInsert {
+                message: format!(
+                    "{} columns exceed the parameter limit of {}",
+                    columns.len(),
+                    parameter_limit
+                ),
+            });
+        }
+
+        // Clones of the connection share its handle, so statements from other threads would
+        // otherwise run inside the savepoint and be rolled back or released along with it
+        let _exclusive_access = self.exclusive_access();
+
+        execute_statement(self, &format!("SAVEPOINT {SAVEPOINT_NAME}"))?;
+
+        match insert_batches(self, table, columns, parameter_limit / columns.len(), rows) {
+            Ok(count) => {
+                execute_statement(self, &format!("RELEASE {SAVEPOINT_NAME}"))?;
+                Ok(count)
+            }
+            Err(error) => {
+                // The original error is more useful to the caller than any failure to roll back
+                let _ = execute_statement(self, &format!("ROLLBACK TO {SAVEPOINT_NAME}"));
+                let _ = execute_statement(self, &format!("RELEASE {SAVEPOINT_NAME}"));
+                Err(error)
+            }
+        }
+    }
+}
+
+fn execute_statement(connection: &SqliteConnection, query_text: &str) -> crate::Result<()> {
+    let mut query = SqliteQuery::new_without_results(connection, query_text)?;
+
+    connection.execute_without_results(&mut query)
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn prepare_batch(
+    connection: &SqliteConnection,
+    table: &str,
+    columns: &[&str],
+    row_count: usize,
+) -> crate::Result<SqliteQuery> {
+    let column_list = columns
+        .iter()
+        .map(|column| quote_identifier(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let row_parameters = format!("({})", vec!["?"; columns.len()].join(", "));
+    let values_list = vec![row_parameters.as_str(); row_count].join(", ");
+
+    SqliteQuery::new_with_change_count(
+        connection,
+        &format!(
+            "INSERT INTO {} ({}) VALUES {}",
+            quote_identifier(table),
+            column_list,
+            values_list
+        ),
+    )
+}
+
+fn insert_batch<'value, Row: AsRef<[ValueUnion<'value>]>>(
+    connection: &SqliteConnection,
+    query: &mut SqliteQuery,
+    batch: &[Row],
+) -> crate::Result<usize> {
+    // Parameters are bound by position because looking them up by name is linear in the number of
+    // parameters, which adds up quickly for batches close to the parameter limit
+    for (parameter_index, value) in batch.iter().flat_map(|row| row.as_ref()).enumerate() {
+        query.bind_value(parameter_index + 1, value)?;
+    }
+
+    connection.execute_with_change_count(query)
+}
+
+fn insert_batches<'value, Rows, Row>(
+    connection: &SqliteConnection,
+    table: &str,
+    columns: &[&str],
+    batch_size: usize,
+    rows: Rows,
+) -> crate::Result<usize>
+where
+    Rows: IntoIterator<Item = Row>,
+    Row: AsRef<[ValueUnion<'value>]>,
+{
+    // Every full batch has the same shape, so one prepared statement is shared between them
+    let mut full_batch_query: Option<SqliteQuery> = None;
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut count = 0;
+
+    for row in rows {
+        if row.as_ref().len() != columns.len() {
+            return Err(crate::Error::RowLengthMismatch {
+                expected: columns.len(),
+                actual: row.as_ref().len(),
+            });
+        }
+
+        batch.push(row);
+
+        if batch.len() == batch_size {
+            let query = match &mut full_batch_query {
+                Some(query) => {
+                    query.reset()?;
+                    query
+                }
+                None => {
+                    full_batch_query.insert(prepare_batch(connection, table, columns, batch_size)?)
+                }
+            };
+
+            count += insert_batch(connection, query, &batch)?;
+            batch.clear();
+        }
+    }
+
+    if !batch.is_empty() {
+        let mut query = prepare_batch(connection, table, columns, batch.len())?;
+        count += insert_batch(connection, &mut query, &batch)?;
+    }
+
+    Ok(count)
+}
@@ -0,0 +1,59 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    connection_pooling::ManageConnection,
+    query_execution::{ExecuteQuery, QueryResult},
+};
+
+use super::{SqliteConnection, SqliteQuery};
+
+const HEALTH_CHECK_QUERY: &str = "SELECT 1";
+
+pub struct SqliteConnectionManager {
+    path: PathBuf,
+}
+
+impl SqliteConnectionManager {
+    pub fn file<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl ManageConnection for SqliteConnectionManager {
+    type Connection = SqliteConnection;
+
+    fn connect(&self) -> crate::Result<Self::Connection> {
+        SqliteConnection::connect_file(&self.path)
+    }
+
+    fn is_valid(&self, connection: &mut Self::Connection) -> crate::Result<()> {
+        let mut query = SqliteQuery::new_with_iterator(connection, HEALTH_CHECK_QUERY)?;
+
+        if let QueryResult::Iterator { mut row_iterator } = connection.execute(&mut query)? {
+            row_iterator.next().transpose()?;
+        }
+
+        Ok(())
+    }
+}
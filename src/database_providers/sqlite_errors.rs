@@ -0,0 +1,113 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use sqlite3_sys as ffi;
+
+use super::sqlite_interrupt::TIMED_OUT_MESSAGE;
+
+lazy_static! {
+    static ref UNIQUE_INDEX_REGEX: Regex =
+        Regex::new(r"^UNIQUE constraint failed: index '(.+)'$").unwrap();
+    static ref UNIQUE_COLUMNS_REGEX: Regex =
+        Regex::new(r"^UNIQUE constraint failed: (.+)$").unwrap();
+    static ref NOT_NULL_REGEX: Regex = Regex::new(r"^NOT NULL constraint failed: (.+)$").unwrap();
+    static ref CHECK_REGEX: Regex = Regex::new(r"^CHECK constraint failed: (.+)$").unwrap();
+    static ref SCHEMA_MISMATCH_REGEXES: [Regex; 4] = [
+        Regex::new(r"^no such table: (.+)$").unwrap(),
+        Regex::new(r"^no such column: (.+)$").unwrap(),
+        Regex::new(r"has no column named (.+)$").unwrap(),
+        Regex::new(r"^no such index: (.+)$").unwrap(),
+    ];
+}
+
+fn capture(regex: &Regex, message: &str) -> Option<String> {
+    regex
+        .captures(message)
+        .and_then(|captures| captures.get(1))
+        .map(|capture| capture.as_str().to_owned())
+}
+
+fn unique_constraint_violation(message: String) -> crate::Error {
+    // Violations of unique indices on expressions name the index instead of the columns
+    if let Some(constraint) = capture(&UNIQUE_INDEX_REGEX, &message) {
+        return crate::Error::UniqueConstraintViolation {
+            constraint: Some(constraint),
+            columns: Vec::new(),
+            message,
+        };
+    }
+
+    crate::Error::UniqueConstraintViolation {
+        constraint: None,
+        columns: capture(&UNIQUE_COLUMNS_REGEX, &message)
+            .map(|columns| columns.split(", ").map(str::to_owned).collect())
+            .unwrap_or_default(),
+        message,
+    }
+}
+
+pub(crate) fn classify_sqlite_error(sqlite_error: sqlite::Error) -> crate::Error {
+    let Some(code) = sqlite_error.code else {
+        return crate::Error::SqliteError { sqlite_error };
+    };
+
+    let message = sqlite_error
+        .message
+        .clone()
+        .unwrap_or_else(|| sqlite_error.to_string());
+
+    // Connections enable extended result codes, so the primary code is in the lowest byte
+    match (code as i32, code as i32 & 0xff) {
+        (ffi::SQLITE_CONSTRAINT_UNIQUE, _)
+        | (ffi::SQLITE_CONSTRAINT_PRIMARYKEY, _)
+        | (ffi::SQLITE_CONSTRAINT_ROWID, _) => unique_constraint_violation(message),
+        (ffi::SQLITE_CONSTRAINT_FOREIGNKEY, _) => crate::Error::ForeignKeyConstraintViolation {
+            // SQLite does not report which foreign key failed
+            constraint: None,
+            message,
+        },
+        (ffi::SQLITE_CONSTRAINT_NOTNULL, _) => crate::Error::NotNullConstraintViolation {
+            column: capture(&NOT_NULL_REGEX, &message),
+            message,
+        },
+        (ffi::SQLITE_CONSTRAINT_CHECK, _) => crate::Error::CheckConstraintViolation {
+            constraint: capture(&CHECK_REGEX, &message),
+            message,
+        },
+        (_, ffi::SQLITE_BUSY) => crate::Error::DatabaseBusy { message },
+        (_, ffi::SQLITE_LOCKED) => crate::Error::DatabaseLocked { message },
+        (_, ffi::SQLITE_READONLY) => crate::Error::DatabaseReadOnly { message },
+        // Timeouts are reported through the same interrupt mechanism as cancellation
+        (_, ffi::SQLITE_INTERRUPT) if message == TIMED_OUT_MESSAGE => crate::Error::QueryTimedOut,
+        (_, ffi::SQLITE_INTERRUPT) => crate::Error::QueryCancelled,
+        (_, ffi::SQLITE_SCHEMA) => crate::Error::SchemaMismatch {
+            object: None,
+            message,
+        },
+        (_, ffi::SQLITE_ERROR) => match SCHEMA_MISMATCH_REGEXES
+            .iter()
+            .find_map(|regex| capture(regex, &message))
+        {
+            Some(object) => crate::Error::SchemaMismatch {
+                object: Some(object),
+                message,
+            },
+            None => crate::Error::SqliteError { sqlite_error },
+        },
+        _ => crate::Error::SqliteError { sqlite_error },
+    }
+}
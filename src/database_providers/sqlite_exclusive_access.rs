@@ -0,0 +1,125 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    thread::{self, ThreadId},
+};
+
+// Lets one thread run a sequence of statements on a shared handle, such as the body of a savepoint,
+// without statements from other threads landing in the middle of it. Every other statement holds
+// shared access while it runs, so exclusive access also waits for statements that are already in
+// flight.
+#[derive(Debug, Default)]
+pub(super) struct ExclusiveAccess {
+    state: Mutex<AccessState>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct AccessState {
+    owner: Option<ThreadId>,
+    waiting_owners: usize,
+    sharers: usize,
+}
+
+pub(super) struct ExclusiveAccessGuard<'access> {
+    access: &'access ExclusiveAccess,
+    previous_owner: Option<ThreadId>,
+}
+
+pub(super) struct SharedAccessGuard<'access> {
+    // `None` when the thread already has exclusive access, which covers its own statements
+    access: Option<&'access ExclusiveAccess>,
+}
+
+impl ExclusiveAccess {
+    pub(super) fn acquire(&self) -> ExclusiveAccessGuard<'_> {
+        let current = thread::current().id();
+        let mut state = self.lock_state();
+
+        if state.owner != Some(current) {
+            state.waiting_owners += 1;
+            state = self.wait_while(state, |state| state.owner.is_some() || state.sharers > 0);
+            state.waiting_owners -= 1;
+        }
+
+        let previous_owner = state.owner.replace(current);
+
+        ExclusiveAccessGuard {
+            access: self,
+            previous_owner,
+        }
+    }
+
+    pub(super) fn share(&self) -> SharedAccessGuard<'_> {
+        let current = thread::current().id();
+        let state = self.lock_state();
+
+        if state.owner == Some(current) {
+            return SharedAccessGuard { access: None };
+        }
+
+        // Waiting for threads that want exclusive access as well keeps a steady stream of
+        // statements from starving them
+        let mut state = self.wait_while(state, |state| {
+            state.owner.is_some() || state.waiting_owners > 0
+        });
+        state.sharers += 1;
+
+        SharedAccessGuard { access: Some(self) }
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, AccessState> {
+        // The state is never left inconsistent by a panic, so poisoning can be safely ignored
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wait_while<'state>(
+        &self,
+        state: MutexGuard<'state, AccessState>,
+        condition: impl FnMut(&mut AccessState) -> bool,
+    ) -> MutexGuard<'state, AccessState> {
+        self.changed
+            .wait_while(state, condition)
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<'access> Drop for ExclusiveAccessGuard<'access> {
+    fn drop(&mut self) {
+        let mut state = self.access.lock_state();
+
+        // Restoring the previous owner keeps an outer guard on the same thread in effect
+        state.owner = self.previous_owner;
+
+        if state.owner.is_none() {
+            self.access.changed.notify_all();
+        }
+    }
+}
+
+impl<'access> Drop for SharedAccessGuard<'access> {
+    fn drop(&mut self) {
+        if let Some(access) = self.access {
+            let mut state = access.lock_state();
+            state.sharers -= 1;
+
+            if state.sharers == 0 {
+                access.changed.notify_all();
+            }
+        }
+    }
+}
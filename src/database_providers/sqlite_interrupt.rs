@@ -0,0 +1,128 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    cell::RefCell,
+    os::raw::{c_int, c_void},
+    ptr,
+    time::{Duration, Instant},
+};
+
+use sqlite3_sys as ffi;
+
+use crate::query_execution::CancelHandle;
+
+const CANCELLED_MESSAGE: &str = "query was cancelled";
+pub(super) const TIMED_OUT_MESSAGE: &str = "query timed out";
+
+// Number of virtual machine instructions between checks for cancellation or timeouts
+const PROGRESS_HANDLER_INTERVAL: c_int = 1000;
+
+thread_local! {
+    static ACTIVE_EXECUTION: RefCell<Option<ExecutionControl>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone)]
+pub(super) struct ExecutionControl {
+    cancel_handle: CancelHandle,
+    deadline: Option<Instant>,
+}
+
+impl ExecutionControl {
+    pub(super) fn new(cancel_handle: CancelHandle, timeout: Option<Duration>) -> Self {
+        Self {
+            cancel_handle,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    pub(super) fn cancel_handle(&self) -> &CancelHandle {
+        &self.cancel_handle
+    }
+
+    fn interruption_message(&self) -> Option<&'static str> {
+        if self.cancel_handle.is_cancelled() {
+            Some(CANCELLED_MESSAGE)
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Some(TIMED_OUT_MESSAGE)
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn run<Value>(
+        &self,
+        operation: impl FnOnce() -> Result<Value, sqlite::Error>,
+    ) -> Result<Value, sqlite::Error> {
+        if let Some(message) = self.interruption_message() {
+            return Err(interrupted(message));
+        }
+
+        // The progress handler runs on the thread that steps the statement, so making this control
+        // the thread's active one scopes cancellation to this query even when the connection is
+        // shared with other threads.
+        let previous = ACTIVE_EXECUTION.with(|active| active.replace(Some(self.clone())));
+        let result = operation();
+        ACTIVE_EXECUTION.with(|active| *active.borrow_mut() = previous);
+
+        match result {
+            Err(error) if is_interrupt(&error) => Err(self
+                .interruption_message()
+                .map(interrupted)
+                .unwrap_or(error)),
+            result => result,
+        }
+    }
+}
+
+pub(super) fn install_progress_handler(sqlite_connection: &sqlite::Connection) {
+    // SAFETY: The raw handle is valid for as long as `sqlite_connection` is alive, and the handler
+    // does not use its argument.
+    unsafe {
+        ffi::sqlite3_progress_handler(
+            sqlite_connection.as_raw(),
+            PROGRESS_HANDLER_INTERVAL,
+            Some(progress_handler),
+            ptr::null_mut(),
+        );
+    }
+}
+
+extern "C" fn progress_handler(_: *mut c_void) -> c_int {
+    ACTIVE_EXECUTION
+        .try_with(|active| {
+            active
+                .borrow()
+                .as_ref()
+                .is_some_and(|control| control.interruption_message().is_some())
+        })
+        .unwrap_or(false) as c_int
+}
+
+fn is_interrupt(error: &sqlite::Error) -> bool {
+    error
+        .code
+        .is_some_and(|code| code as i32 & 0xff == ffi::SQLITE_INTERRUPT)
+}
+
+fn interrupted(message: &str) -> sqlite::Error {
+    sqlite::Error {
+        code: Some(ffi::SQLITE_INTERRUPT as isize),
+        message: Some(message.to_owned()),
+    }
+}
@@ -13,8 +13,20 @@
 // You should have received a copy of the GNU General Public License along with Bedrock ORM. If
 // not, see <https://www.gnu.org/licenses/>.
 
+mod compare_value;
+mod convert_value;
 mod data_type;
+mod display_value;
+mod duration_millis;
+mod owned_value_union;
+mod parse_value;
+mod sql_enum;
+mod sql_type;
 mod value_union;
 
 pub use data_type::DataType;
+pub(crate) use duration_millis::{duration_from_millis, duration_to_millis};
+pub use owned_value_union::OwnedValueUnion;
+pub use sql_enum::SqlEnum;
+pub use sql_type::SqlType;
 pub use value_union::ValueUnion;
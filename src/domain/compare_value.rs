@@ -0,0 +1,216 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::cmp::Ordering;
+
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+
+use super::{OwnedValueUnion, ValueUnion};
+
+// Numeric variants compare by value regardless of their width or signedness, so `U8(1)` equals
+// `I64(1)` and `F64(1.5)` sorts between them and `I32(2)`
+#[derive(Clone, Copy)]
+enum Number {
+    Integer(i128),
+    // Only used for values above `i128::MAX`
+    LargeUnsigned(u128),
+    Float(f64),
+    #[cfg(feature = "decimal")]
+    Decimal(Decimal),
+}
+
+impl<'value> ValueUnion<'value> {
+    fn number(&self) -> Option<Number> {
+        match self {
+            Self::U8(value) => Some(Number::Integer(*value as i128)),
+            Self::U16(value) => Some(Number::Integer(*value as i128)),
+            Self::U32(value) => Some(Number::Integer(*value as i128)),
+            Self::U64(value) => Some(Number::Integer(*value as i128)),
+            Self::U128(value) => Some(
+                i128::try_from(*value)
+                    .map(Number::Integer)
+                    .unwrap_or(Number::LargeUnsigned(*value)),
+            ),
+            Self::I8(value) => Some(Number::Integer(*value as i128)),
+            Self::I16(value) => Some(Number::Integer(*value as i128)),
+            Self::I32(value) => Some(Number::Integer(*value as i128)),
+            Self::I64(value) => Some(Number::Integer(*value as i128)),
+            Self::I128(value) => Some(Number::Integer(*value)),
+            Self::F32(value) => Some(Number::Float(*value as f64)),
+            Self::F64(value) => Some(Number::Float(*value)),
+            #[cfg(feature = "decimal")]
+            Self::Decimal(value) => Some(Number::Decimal(**value)),
+            _ => None,
+        }
+    }
+}
+
+impl<'value> PartialEq for ValueUnion<'value> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // Values that have no ordering can still be equal
+            #[cfg(feature = "json")]
+            (Self::Json(left), Self::Json(right)) => left == right,
+            (Self::List(left), Self::List(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .zip(right.iter())
+                        .all(|(left, right)| left.as_value_union() == right.as_value_union())
+            }
+            _ => self.partial_cmp(other) == Some(Ordering::Equal),
+        }
+    }
+}
+
+impl<'value> PartialOrd for ValueUnion<'value> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if let (Some(left), Some(right)) = (self.number(), other.number()) {
+            return compare_numbers(left, right);
+        }
+
+        match (self, other) {
+            (Self::Null, Self::Null) => Some(Ordering::Equal),
+            (Self::Bool(left), Self::Bool(right)) => left.partial_cmp(right),
+            (Self::String(left), Self::String(right)) => left.partial_cmp(right),
+            (Self::Bytestring(left), Self::Bytestring(right)) => left.partial_cmp(right),
+            (Self::Date(left), Self::Date(right)) => left.partial_cmp(right),
+            (Self::DateTime(left), Self::DateTime(right)) => left.partial_cmp(right),
+            // Offset-aware datetimes compare as instants
+            (Self::DateTimeUtc(left), Self::DateTimeUtc(right)) => left.partial_cmp(right),
+            (Self::DateTimeUtc(left), Self::DateTimeTz(right)) => left.partial_cmp(right),
+            (Self::DateTimeTz(left), Self::DateTimeUtc(right)) => left.partial_cmp(right),
+            (Self::DateTimeTz(left), Self::DateTimeTz(right)) => left.partial_cmp(right),
+            (Self::Time(left), Self::Time(right)) => left.partial_cmp(right),
+            (Self::Duration(left), Self::Duration(right)) => left.partial_cmp(right),
+            #[cfg(feature = "uuid")]
+            (Self::Uuid(left), Self::Uuid(right)) => left.partial_cmp(right),
+            #[cfg(feature = "json")]
+            (Self::Json(left), Self::Json(right)) if left == right => Some(Ordering::Equal),
+            (Self::List(left), Self::List(right)) => {
+                for (left, right) in left.iter().zip(right.iter()) {
+                    match left.as_value_union().partial_cmp(&right.as_value_union()) {
+                        Some(Ordering::Equal) => {}
+                        ordering => return ordering,
+                    }
+                }
+
+                left.len().partial_cmp(&right.len())
+            }
+            _ => None,
+        }
+    }
+}
+
+// Owned values compare structurally, so that a value read back as the wrong variant is caught,
+// while these compare them the same way as the values they borrow as
+impl OwnedValueUnion {
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.as_value_union() == other.as_value_union()
+    }
+
+    pub fn semantic_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_value_union().partial_cmp(&other.as_value_union())
+    }
+}
+
+fn compare_numbers(left: Number, right: Number) -> Option<Ordering> {
+    match (left, right) {
+        (Number::Integer(left), Number::Integer(right)) => Some(left.cmp(&right)),
+        (Number::LargeUnsigned(left), Number::LargeUnsigned(right)) => Some(left.cmp(&right)),
+        (Number::Integer(_), Number::LargeUnsigned(_)) => Some(Ordering::Less),
+        (Number::LargeUnsigned(_), Number::Integer(_)) => Some(Ordering::Greater),
+        (Number::Float(left), Number::Float(right)) => left.partial_cmp(&right),
+        (Number::Integer(left), Number::Float(right)) => compare_integer_to_float(left, right),
+        (Number::Float(left), Number::Integer(right)) => {
+            compare_integer_to_float(right, left).map(Ordering::reverse)
+        }
+        (Number::LargeUnsigned(left), Number::Float(right)) => {
+            compare_large_unsigned_to_float(left, right)
+        }
+        (Number::Float(left), Number::LargeUnsigned(right)) => {
+            compare_large_unsigned_to_float(right, left).map(Ordering::reverse)
+        }
+        #[cfg(feature = "decimal")]
+        (Number::Decimal(left), Number::Decimal(right)) => Some(left.cmp(&right)),
+        #[cfg(feature = "decimal")]
+        (Number::Decimal(left), right) => compare_decimal(left, right),
+        #[cfg(feature = "decimal")]
+        (left, Number::Decimal(right)) => compare_decimal(right, left).map(Ordering::reverse),
+    }
+}
+
+// Converting the integer to a float would lose precision above 2^53, so this compares the
+// float's integral part exactly and then uses its fractional part to break ties
+fn compare_integer_to_float(left: i128, right: f64) -> Option<Ordering> {
+    if right.is_nan() {
+        return None;
+    }
+
+    if right >= 2f64.powi(127) {
+        return Some(Ordering::Less);
+    }
+
+    if right < -(2f64.powi(127)) {
+        return Some(Ordering::Greater);
+    }
+
+    let integral = right.trunc();
+
+    Some(
+        left.cmp(&(integral as i128))
+            .then_with(|| 0f64.total_cmp(&(right - integral))),
+    )
+}
+
+fn compare_large_unsigned_to_float(left: u128, right: f64) -> Option<Ordering> {
+    if right.is_nan() {
+        return None;
+    }
+
+    if right >= 2f64.powi(128) {
+        return Some(Ordering::Less);
+    }
+
+    if right < 2f64.powi(127) {
+        return Some(Ordering::Greater);
+    }
+
+    let integral = right.trunc();
+
+    Some(
+        left.cmp(&(integral as u128))
+            .then_with(|| 0f64.total_cmp(&(right - integral))),
+    )
+}
+
+#[cfg(feature = "decimal")]
+fn compare_decimal(left: Decimal, right: Number) -> Option<Ordering> {
+    match right {
+        Number::Integer(right) => match Decimal::try_from_i128_with_scale(right, 0) {
+            Ok(right) => Some(left.cmp(&right)),
+            // The integer is beyond the range of a decimal in one direction or the other
+            Err(_) => Some(0.cmp(&right)),
+        },
+        Number::LargeUnsigned(_) => Some(Ordering::Less),
+        Number::Float(right) if right.is_nan() => None,
+        Number::Float(right) => match Decimal::try_from(right) {
+            Ok(right) => Some(left.cmp(&right)),
+            Err(_) => Some(0f64.total_cmp(&right)),
+        },
+        Number::Decimal(right) => Some(left.cmp(&right)),
+    }
+}
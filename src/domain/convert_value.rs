@@ -0,0 +1,213 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::str::FromStr;
+
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+use super::{duration_from_millis, DataType, OwnedValueUnion, ValueUnion};
+
+const DATE_FORMAT: &str = "%F";
+const NAIVE_DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+const OFFSET_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%:z";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+impl<'value> ValueUnion<'value> {
+    // Databases usually store values in a narrower set of types than they were written with, so
+    // this converts them back from the forms they are stored in
+    pub fn convert_to(&self, data_type: &DataType) -> crate::Result<OwnedValueUnion> {
+        if self.data_type() == *data_type {
+            return Ok(OwnedValueUnion::from(self));
+        }
+
+        if let DataType::Nullable(data_type) = data_type {
+            return match self {
+                Self::Null => Ok(OwnedValueUnion::Null),
+                _ => self.convert_to(data_type),
+            };
+        }
+
+        let converted = match data_type {
+            DataType::Null | DataType::List | DataType::Nullable(_) => None,
+            DataType::Bool => match self {
+                Self::String(value) if value.as_str() == "true" => {
+                    Some(OwnedValueUnion::Bool(true))
+                }
+                Self::String(value) if value.as_str() == "false" => {
+                    Some(OwnedValueUnion::Bool(false))
+                }
+                _ => match self.integral_value() {
+                    Some(0) => Some(OwnedValueUnion::Bool(false)),
+                    Some(1) => Some(OwnedValueUnion::Bool(true)),
+                    _ => None,
+                },
+            },
+            DataType::U8 => self.convert_integral(OwnedValueUnion::U8),
+            DataType::U16 => self.convert_integral(OwnedValueUnion::U16),
+            DataType::U32 => self.convert_integral(OwnedValueUnion::U32),
+            // Values that don't fit in a 64-bit signed integer are stored according to the
+            // connection's overflow policy, as either decimal text or big-endian bytes
+            DataType::U64 => self.convert_wide_integral(u64::from_be_bytes, OwnedValueUnion::U64),
+            DataType::U128 => {
+                self.convert_wide_integral(u128::from_be_bytes, OwnedValueUnion::U128)
+            }
+            DataType::I8 => self.convert_integral(OwnedValueUnion::I8),
+            DataType::I16 => self.convert_integral(OwnedValueUnion::I16),
+            DataType::I32 => self.convert_integral(OwnedValueUnion::I32),
+            DataType::I64 => self.convert_integral(OwnedValueUnion::I64),
+            DataType::I128 => {
+                self.convert_wide_integral(i128::from_be_bytes, OwnedValueUnion::I128)
+            }
+            DataType::F32 => match self {
+                Self::F64(value) => Some(OwnedValueUnion::F32(*value as f32)),
+                _ => self
+                    .integral_value()
+                    .map(|value| OwnedValueUnion::F32(value as f32)),
+            },
+            DataType::F64 => match self {
+                Self::F32(value) => Some(OwnedValueUnion::F64(*value as f64)),
+                _ => self
+                    .integral_value()
+                    .map(|value| OwnedValueUnion::F64(value as f64)),
+            },
+            DataType::String | DataType::Bytestring => None,
+            DataType::Date => match self {
+                Self::String(value) => NaiveDate::parse_from_str(value, DATE_FORMAT)
+                    .ok()
+                    .map(OwnedValueUnion::Date),
+                _ => None,
+            },
+            DataType::DateTime => match self {
+                Self::String(value) => NAIVE_DATETIME_FORMATS
+                    .iter()
+                    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+                    .map(OwnedValueUnion::DateTime),
+                _ => None,
+            },
+            DataType::DateTimeUtc => match self {
+                Self::String(value) => parse_offset_datetime(value)
+                    .map(|value| OwnedValueUnion::DateTimeUtc(value.with_timezone(&Utc))),
+                Self::DateTimeTz(value) => {
+                    Some(OwnedValueUnion::DateTimeUtc(value.with_timezone(&Utc)))
+                }
+                _ => None,
+            },
+            DataType::DateTimeTz => match self {
+                Self::String(value) => {
+                    parse_offset_datetime(value).map(OwnedValueUnion::DateTimeTz)
+                }
+                Self::DateTimeUtc(value) => Some(OwnedValueUnion::DateTimeTz(value.fixed_offset())),
+                _ => None,
+            },
+            DataType::Time => match self {
+                Self::String(value) => NaiveTime::parse_from_str(value, TIME_FORMAT)
+                    .ok()
+                    .map(OwnedValueUnion::Time),
+                _ => None,
+            },
+            DataType::Duration => self
+                .integral_value()
+                .and_then(|value| u64::try_from(value).ok())
+                .map(|value| OwnedValueUnion::Duration(duration_from_millis(value))),
+            #[cfg(feature = "decimal")]
+            DataType::Decimal => match self {
+                Self::String(value) => Decimal::from_str_exact(value)
+                    .ok()
+                    .map(OwnedValueUnion::Decimal),
+                // Columns with numeric affinity may have turned the stored text into a number
+                Self::F64(value) => Decimal::try_from(*value).ok().map(OwnedValueUnion::Decimal),
+                _ => self
+                    .integral_value()
+                    .and_then(|value| Decimal::try_from_i128_with_scale(value, 0).ok())
+                    .map(OwnedValueUnion::Decimal),
+            },
+            // Either storage form is accepted, since it can differ between connections
+            #[cfg(feature = "uuid")]
+            DataType::Uuid => match self {
+                Self::String(value) => Uuid::parse_str(value).ok().map(OwnedValueUnion::Uuid),
+                Self::Bytestring(value) => Uuid::from_slice(value).ok().map(OwnedValueUnion::Uuid),
+                _ => None,
+            },
+            // Text that isn't valid JSON is reported as such, rather than as a type mismatch
+            #[cfg(feature = "json")]
+            DataType::Json => match self {
+                Self::String(value) => Some(OwnedValueUnion::Json(
+                    serde_json::from_str(value).map_err(|error| crate::Error::InvalidJson {
+                        message: error.to_string(),
+                    })?,
+                )),
+                // JSON1 functions such as json_extract return scalars as plain SQL values
+                Self::I64(value) => Some(OwnedValueUnion::Json(serde_json::Value::from(*value))),
+                Self::F64(value) => serde_json::Number::from_f64(*value)
+                    .map(|value| OwnedValueUnion::Json(serde_json::Value::Number(value))),
+                _ => None,
+            },
+        };
+
+        converted.ok_or_else(|| crate::Error::ValueCannotBeAccessedAsRequestedType {
+            value_type: self.data_type(),
+            requested_type: data_type.clone(),
+        })
+    }
+
+    fn integral_value(&self) -> Option<i128> {
+        match self {
+            Self::U8(value) => Some(*value as i128),
+            Self::U16(value) => Some(*value as i128),
+            Self::U32(value) => Some(*value as i128),
+            Self::U64(value) => Some(*value as i128),
+            Self::U128(value) => i128::try_from(*value).ok(),
+            Self::I8(value) => Some(*value as i128),
+            Self::I16(value) => Some(*value as i128),
+            Self::I32(value) => Some(*value as i128),
+            Self::I64(value) => Some(*value as i128),
+            Self::I128(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn convert_integral<Integral: TryFrom<i128>>(
+        &self,
+        variant: impl FnOnce(Integral) -> OwnedValueUnion,
+    ) -> Option<OwnedValueUnion> {
+        self.integral_value()
+            .and_then(|value| Integral::try_from(value).ok())
+            .map(variant)
+    }
+
+    fn convert_wide_integral<Integral: TryFrom<i128> + FromStr, const WIDTH: usize>(
+        &self,
+        from_be_bytes: impl FnOnce([u8; WIDTH]) -> Integral,
+        variant: impl FnOnce(Integral) -> OwnedValueUnion,
+    ) -> Option<OwnedValueUnion> {
+        match self {
+            Self::String(value) => value.parse().ok().map(variant),
+            Self::Bytestring(value) => <[u8; WIDTH]>::try_from(*value)
+                .ok()
+                .map(|bytes| variant(from_be_bytes(bytes))),
+            _ => self.convert_integral(variant),
+        }
+    }
+}
+
+fn parse_offset_datetime(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_str(value, OFFSET_DATETIME_FORMAT))
+        .ok()
+}
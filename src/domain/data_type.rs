@@ -13,26 +13,61 @@
 // You should have received a copy of the GNU General Public License along with Bedrock ORM. If
 // not, see <https://www.gnu.org/licenses/>.
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataType {
+    Null,
     Bool,
     U8,
     U16,
     U32,
     U64,
+    U128,
     I8,
     I16,
     I32,
     I64,
+    I128,
     F32,
     F64,
     String,
     Bytestring,
     Date,
     DateTime,
+    DateTimeUtc,
+    DateTimeTz,
+    Time,
+    Duration,
+    #[cfg(feature = "decimal")]
+    Decimal,
+    #[cfg(feature = "uuid")]
+    Uuid,
+    #[cfg(feature = "json")]
+    Json,
+    List,
+    Nullable(Box<DataType>),
 }
 
 impl DataType {
+    pub fn nullable(self) -> Self {
+        match self {
+            Self::Null | Self::Nullable(_) => self,
+            _ => Self::Nullable(Box::new(self)),
+        }
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, Self::Null | Self::Nullable(_))
+    }
+
+    // The type of the values a nullable type holds when they aren't null
+    pub fn non_nullable(&self) -> &DataType {
+        match self {
+            Self::Nullable(data_type) => data_type,
+            _ => self,
+        }
+    }
+
     pub fn is_integral(&self) -> bool {
         matches!(
             self,
@@ -40,10 +75,12 @@ impl DataType {
                 | Self::U16
                 | Self::U32
                 | Self::U64
+                | Self::U128
                 | Self::I8
                 | Self::I16
                 | Self::I32
                 | Self::I64
+                | Self::I128
         )
     }
 
@@ -53,10 +90,12 @@ impl DataType {
             Self::U16 => Some(false),
             Self::U32 => Some(false),
             Self::U64 => Some(false),
+            Self::U128 => Some(false),
             Self::I8 => Some(true),
             Self::I16 => Some(true),
             Self::I32 => Some(true),
             Self::I64 => Some(true),
+            Self::I128 => Some(true),
             _ => None,
         }
     }
@@ -67,10 +106,12 @@ impl DataType {
             Self::U16 => Some(16),
             Self::U32 => Some(32),
             Self::U64 => Some(64),
+            Self::U128 => Some(128),
             Self::I8 => Some(8),
             Self::I16 => Some(16),
             Self::I32 => Some(32),
             Self::I64 => Some(64),
+            Self::I128 => Some(128),
             _ => None,
         }
     }
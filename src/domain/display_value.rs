@@ -0,0 +1,104 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt::{self, Display, Formatter};
+
+use super::{duration_to_millis, OwnedValueUnion, ValueUnion};
+
+const NAIVE_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+// Renders values the way they would be written as SQL literals, although this is meant for
+// logging and diffing rather than for building queries, which should bind parameters instead
+impl<'value> Display for ValueUnion<'value> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(formatter, "NULL"),
+            Self::Bool(true) => write!(formatter, "TRUE"),
+            Self::Bool(false) => write!(formatter, "FALSE"),
+            Self::U8(value) => write!(formatter, "{value}"),
+            Self::U16(value) => write!(formatter, "{value}"),
+            Self::U32(value) => write!(formatter, "{value}"),
+            Self::U64(value) => write!(formatter, "{value}"),
+            Self::U128(value) => write!(formatter, "{value}"),
+            Self::I8(value) => write!(formatter, "{value}"),
+            Self::I16(value) => write!(formatter, "{value}"),
+            Self::I32(value) => write!(formatter, "{value}"),
+            Self::I64(value) => write!(formatter, "{value}"),
+            Self::I128(value) => write!(formatter, "{value}"),
+            Self::F32(value) => write_float(formatter, *value as f64),
+            Self::F64(value) => write_float(formatter, *value),
+            Self::String(value) => write_quoted(formatter, value),
+            Self::Bytestring(value) => {
+                write!(formatter, "X'")?;
+
+                for byte in value.iter() {
+                    write!(formatter, "{byte:02X}")?;
+                }
+
+                write!(formatter, "'")
+            }
+            Self::Date(value) => write!(formatter, "'{value}'"),
+            Self::DateTime(value) => write!(formatter, "'{}'", value.format(NAIVE_DATETIME_FORMAT)),
+            Self::DateTimeUtc(value) => write!(formatter, "'{}'", value.to_rfc3339()),
+            Self::DateTimeTz(value) => write!(formatter, "'{}'", value.to_rfc3339()),
+            Self::Time(value) => write!(formatter, "'{}'", value.format(TIME_FORMAT)),
+            Self::Duration(value) => write!(formatter, "{}", duration_to_millis(value)),
+            #[cfg(feature = "decimal")]
+            Self::Decimal(value) => write!(formatter, "{value}"),
+            #[cfg(feature = "uuid")]
+            Self::Uuid(value) => write!(formatter, "'{value}'"),
+            #[cfg(feature = "json")]
+            Self::Json(value) => write_quoted(formatter, &value.to_string()),
+            Self::List(values) => {
+                write!(formatter, "(")?;
+
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(formatter, ", ")?;
+                    }
+
+                    write!(formatter, "{}", value.as_value_union())?;
+                }
+
+                write!(formatter, ")")
+            }
+        }
+    }
+}
+
+impl Display for OwnedValueUnion {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        self.as_value_union().fmt(formatter)
+    }
+}
+
+fn write_float(formatter: &mut Formatter<'_>, value: f64) -> fmt::Result {
+    if value.is_nan() {
+        write!(formatter, "'NaN'")
+    } else if value.is_infinite() {
+        write!(
+            formatter,
+            "'{}Infinity'",
+            if value.is_sign_negative() { "-" } else { "" }
+        )
+    } else {
+        write!(formatter, "{value:?}")
+    }
+}
+
+fn write_quoted(formatter: &mut Formatter<'_>, value: &str) -> fmt::Result {
+    write!(formatter, "'{}'", value.replace('\'', "''"))
+}
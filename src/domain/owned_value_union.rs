@@ -0,0 +1,140 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::time::Duration;
+
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+use super::{DataType, ValueUnion};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OwnedValueUnion {
+    Null,
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Bytestring(Vec<u8>),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    DateTimeUtc(DateTime<Utc>),
+    DateTimeTz(DateTime<FixedOffset>),
+    Time(NaiveTime),
+    Duration(Duration),
+    #[cfg(feature = "decimal")]
+    Decimal(Decimal),
+    #[cfg(feature = "uuid")]
+    Uuid(Uuid),
+    #[cfg(feature = "json")]
+    Json(serde_json::Value),
+    List(Vec<OwnedValueUnion>),
+}
+
+impl OwnedValueUnion {
+    pub fn data_type(&self) -> DataType {
+        self.as_value_union().data_type()
+    }
+
+    pub fn as_value_union(&self) -> ValueUnion<'_> {
+        match self {
+            Self::Null => ValueUnion::Null,
+            Self::Bool(value) => ValueUnion::Bool(*value),
+            Self::U8(value) => ValueUnion::U8(*value),
+            Self::U16(value) => ValueUnion::U16(*value),
+            Self::U32(value) => ValueUnion::U32(*value),
+            Self::U64(value) => ValueUnion::U64(*value),
+            Self::U128(value) => ValueUnion::U128(*value),
+            Self::I8(value) => ValueUnion::I8(*value),
+            Self::I16(value) => ValueUnion::I16(*value),
+            Self::I32(value) => ValueUnion::I32(*value),
+            Self::I64(value) => ValueUnion::I64(*value),
+            Self::I128(value) => ValueUnion::I128(*value),
+            Self::F32(value) => ValueUnion::F32(*value),
+            Self::F64(value) => ValueUnion::F64(*value),
+            Self::String(value) => ValueUnion::String(value),
+            Self::Bytestring(value) => ValueUnion::Bytestring(value),
+            Self::Date(value) => ValueUnion::Date(value),
+            Self::DateTime(value) => ValueUnion::DateTime(value),
+            Self::DateTimeUtc(value) => ValueUnion::DateTimeUtc(value),
+            Self::DateTimeTz(value) => ValueUnion::DateTimeTz(value),
+            Self::Time(value) => ValueUnion::Time(value),
+            Self::Duration(value) => ValueUnion::Duration(value),
+            #[cfg(feature = "decimal")]
+            Self::Decimal(value) => ValueUnion::Decimal(value),
+            #[cfg(feature = "uuid")]
+            Self::Uuid(value) => ValueUnion::Uuid(value),
+            #[cfg(feature = "json")]
+            Self::Json(value) => ValueUnion::Json(value),
+            Self::List(value) => ValueUnion::List(value),
+        }
+    }
+}
+
+impl<'value> From<&ValueUnion<'value>> for OwnedValueUnion {
+    fn from(value: &ValueUnion<'value>) -> Self {
+        match value {
+            ValueUnion::Null => Self::Null,
+            ValueUnion::Bool(value) => Self::Bool(*value),
+            ValueUnion::U8(value) => Self::U8(*value),
+            ValueUnion::U16(value) => Self::U16(*value),
+            ValueUnion::U32(value) => Self::U32(*value),
+            ValueUnion::U64(value) => Self::U64(*value),
+            ValueUnion::U128(value) => Self::U128(*value),
+            ValueUnion::I8(value) => Self::I8(*value),
+            ValueUnion::I16(value) => Self::I16(*value),
+            ValueUnion::I32(value) => Self::I32(*value),
+            ValueUnion::I64(value) => Self::I64(*value),
+            ValueUnion::I128(value) => Self::I128(*value),
+            ValueUnion::F32(value) => Self::F32(*value),
+            ValueUnion::F64(value) => Self::F64(*value),
+            ValueUnion::String(value) => Self::String((*value).clone()),
+            ValueUnion::Bytestring(value) => Self::Bytestring(value.to_vec()),
+            ValueUnion::Date(value) => Self::Date(**value),
+            ValueUnion::DateTime(value) => Self::DateTime(**value),
+            ValueUnion::DateTimeUtc(value) => Self::DateTimeUtc(**value),
+            ValueUnion::DateTimeTz(value) => Self::DateTimeTz(**value),
+            ValueUnion::Time(value) => Self::Time(**value),
+            ValueUnion::Duration(value) => Self::Duration(**value),
+            #[cfg(feature = "decimal")]
+            ValueUnion::Decimal(value) => Self::Decimal(**value),
+            #[cfg(feature = "uuid")]
+            ValueUnion::Uuid(value) => Self::Uuid(**value),
+            #[cfg(feature = "json")]
+            ValueUnion::Json(value) => Self::Json((*value).clone()),
+            ValueUnion::List(value) => Self::List(value.to_vec()),
+        }
+    }
+}
+
+impl<'value> From<ValueUnion<'value>> for OwnedValueUnion {
+    fn from(value: ValueUnion<'value>) -> Self {
+        Self::from(&value)
+    }
+}
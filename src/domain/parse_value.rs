@@ -0,0 +1,78 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::str::FromStr;
+
+use super::{duration_from_millis, DataType, OwnedValueUnion, ValueUnion};
+
+impl OwnedValueUnion {
+    // Parses plain text, such as a value from a configuration file, rather than a SQL literal
+    pub fn parse(text: &str, data_type: &DataType) -> crate::Result<Self> {
+        let parsed = match data_type {
+            DataType::Null => text.eq_ignore_ascii_case("null").then_some(Self::Null),
+            DataType::Nullable(data_type) => {
+                if text.eq_ignore_ascii_case("null") {
+                    Some(Self::Null)
+                } else {
+                    return Self::parse(text, data_type);
+                }
+            }
+            DataType::U8 => parse_from_str(text).map(Self::U8),
+            DataType::U16 => parse_from_str(text).map(Self::U16),
+            DataType::U32 => parse_from_str(text).map(Self::U32),
+            DataType::U64 => parse_from_str(text).map(Self::U64),
+            DataType::U128 => parse_from_str(text).map(Self::U128),
+            DataType::I8 => parse_from_str(text).map(Self::I8),
+            DataType::I16 => parse_from_str(text).map(Self::I16),
+            DataType::I32 => parse_from_str(text).map(Self::I32),
+            DataType::I64 => parse_from_str(text).map(Self::I64),
+            DataType::I128 => parse_from_str(text).map(Self::I128),
+            DataType::F32 => parse_from_str(text).map(Self::F32),
+            DataType::F64 => parse_from_str(text).map(Self::F64),
+            DataType::String => Some(Self::String(text.to_owned())),
+            // Bytestrings are written as hexadecimal
+            DataType::Bytestring => parse_hex(text).map(Self::Bytestring),
+            DataType::Duration => {
+                parse_from_str(text).map(|value| Self::Duration(duration_from_millis(value)))
+            }
+            DataType::List => None,
+            // The rest are parsed from the same text they are stored as
+            _ => ValueUnion::String(&text.to_owned())
+                .convert_to(data_type)
+                .ok(),
+        };
+
+        parsed.ok_or_else(|| crate::Error::InvalidValueText {
+            data_type: data_type.clone(),
+            text: text.to_owned(),
+        })
+    }
+}
+
+fn parse_from_str<Value: FromStr>(text: &str) -> Option<Value> {
+    text.parse().ok()
+}
+
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => {
+                u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()
+            }
+            _ => None,
+        })
+        .collect()
+}
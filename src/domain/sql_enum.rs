@@ -0,0 +1,68 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use super::{OwnedValueUnion, SqlType};
+
+// Implemented by `sql_enum!` for fieldless enums stored as one of a fixed set of values
+pub trait SqlEnum: SqlType {
+    fn allowed_values() -> Vec<OwnedValueUnion>;
+}
+
+// Maps each variant of an existing fieldless enum to a text or integer value, for example
+// `sql_enum!(Status as String { Active => "active", Banned => "banned" })`
+#[macro_export]
+macro_rules! sql_enum {
+    ($enum:ident as $data_type:ident { $($variant:ident => $value:literal),+ $(,)? }) => {
+        impl $crate::domain::SqlType for $enum {
+            fn data_type() -> $crate::domain::DataType {
+                $crate::domain::DataType::$data_type
+            }
+
+            fn to_sql_value(&self) -> $crate::domain::OwnedValueUnion {
+                match self {
+                    $(Self::$variant => $crate::domain::OwnedValueUnion::$data_type($value.into()),)+
+                }
+            }
+
+            fn from_sql_value(
+                value: $crate::domain::OwnedValueUnion,
+            ) -> $crate::Result<Self> {
+                let $crate::domain::OwnedValueUnion::$data_type(stored) = value else {
+                    return Err($crate::Error::ValueCannotBeAccessedAsRequestedType {
+                        value_type: value.as_value_union().data_type(),
+                        requested_type: $crate::domain::DataType::$data_type,
+                    });
+                };
+
+                $(
+                    if stored == $value {
+                        return Ok(Self::$variant);
+                    }
+                )+
+
+                Err($crate::Error::UnknownEnumValue {
+                    enum_name: stringify!($enum),
+                    value: stored.to_string(),
+                })
+            }
+        }
+
+        impl $crate::domain::SqlEnum for $enum {
+            fn allowed_values() -> Vec<$crate::domain::OwnedValueUnion> {
+                vec![$($crate::domain::OwnedValueUnion::$data_type($value.into()),)+]
+            }
+        }
+    };
+}
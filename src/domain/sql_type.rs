@@ -0,0 +1,110 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::time::Duration;
+
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+use super::{DataType, OwnedValueUnion};
+
+// Maps a Rust type to and from one of the built-in data types, which is how custom types are
+// bound, read and declared
+pub trait SqlType: Sized {
+    fn data_type() -> DataType;
+
+    fn to_sql_value(&self) -> OwnedValueUnion;
+
+    // Values are converted to `data_type()` before they are passed in
+    fn from_sql_value(value: OwnedValueUnion) -> crate::Result<Self>;
+}
+
+impl<Value: SqlType> SqlType for Option<Value> {
+    fn data_type() -> DataType {
+        Value::data_type().nullable()
+    }
+
+    fn to_sql_value(&self) -> OwnedValueUnion {
+        self.as_ref()
+            .map_or(OwnedValueUnion::Null, SqlType::to_sql_value)
+    }
+
+    fn from_sql_value(value: OwnedValueUnion) -> crate::Result<Self> {
+        match value {
+            OwnedValueUnion::Null => Ok(None),
+            _ => Value::from_sql_value(value).map(Some),
+        }
+    }
+}
+
+macro_rules! impl_sql_type {
+    ($($(#[$attribute:meta])* $type:ty => $variant:ident,)*) => {
+        $(
+            $(#[$attribute])*
+            impl SqlType for $type {
+                fn data_type() -> DataType {
+                    DataType::$variant
+                }
+
+                fn to_sql_value(&self) -> OwnedValueUnion {
+                    OwnedValueUnion::$variant(self.clone())
+                }
+
+                fn from_sql_value(value: OwnedValueUnion) -> crate::Result<Self> {
+                    match value {
+                        OwnedValueUnion::$variant(value) => Ok(value),
+                        _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                            value_type: value.as_value_union().data_type(),
+                            requested_type: DataType::$variant,
+                        }),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_sql_type! {
+    bool => Bool,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    u128 => U128,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    i128 => I128,
+    f32 => F32,
+    f64 => F64,
+    String => String,
+    Vec<u8> => Bytestring,
+    NaiveDate => Date,
+    NaiveDateTime => DateTime,
+    DateTime<Utc> => DateTimeUtc,
+    DateTime<FixedOffset> => DateTimeTz,
+    NaiveTime => Time,
+    Duration => Duration,
+    #[cfg(feature = "decimal")]
+    Decimal => Decimal,
+    #[cfg(feature = "uuid")]
+    Uuid => Uuid,
+    #[cfg(feature = "json")]
+    serde_json::Value => Json,
+}
@@ -13,46 +13,101 @@
 // You should have received a copy of the GNU General Public License along with Bedrock ORM. If
 // not, see <https://www.gnu.org/licenses/>.
 
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::time::Duration;
 
-use super::DataType;
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
 
+use super::{DataType, OwnedValueUnion};
+
+#[derive(Clone, Copy, Debug)]
 pub enum ValueUnion<'value> {
+    Null,
     Bool(bool),
     U8(u8),
     U16(u16),
     U32(u32),
     U64(u64),
+    U128(u128),
     I8(i8),
     I16(i16),
     I32(i32),
     I64(i64),
+    I128(i128),
     F32(f32),
     F64(f64),
     String(&'value String),
     Bytestring(&'value [u8]),
     Date(&'value NaiveDate),
     DateTime(&'value NaiveDateTime),
+    DateTimeUtc(&'value DateTime<Utc>),
+    DateTimeTz(&'value DateTime<FixedOffset>),
+    Time(&'value NaiveTime),
+    Duration(&'value Duration),
+    #[cfg(feature = "decimal")]
+    Decimal(&'value Decimal),
+    #[cfg(feature = "uuid")]
+    Uuid(&'value Uuid),
+    #[cfg(feature = "json")]
+    Json(&'value serde_json::Value),
+    List(&'value [OwnedValueUnion]),
 }
 
 impl<'value> ValueUnion<'value> {
     pub fn data_type(&self) -> DataType {
         match self {
+            Self::Null => DataType::Null,
             Self::Bool(_) => DataType::Bool,
             Self::U8(_) => DataType::U8,
             Self::U16(_) => DataType::U16,
             Self::U32(_) => DataType::U32,
             Self::U64(_) => DataType::U64,
+            Self::U128(_) => DataType::U128,
             Self::I8(_) => DataType::I8,
             Self::I16(_) => DataType::I16,
             Self::I32(_) => DataType::I32,
             Self::I64(_) => DataType::I64,
+            Self::I128(_) => DataType::I128,
             Self::F32(_) => DataType::F32,
             Self::F64(_) => DataType::F64,
             Self::String(_) => DataType::String,
             Self::Bytestring(_) => DataType::Bytestring,
             Self::Date(_) => DataType::Date,
             Self::DateTime(_) => DataType::DateTime,
+            Self::DateTimeUtc(_) => DataType::DateTimeUtc,
+            Self::DateTimeTz(_) => DataType::DateTimeTz,
+            Self::Time(_) => DataType::Time,
+            Self::Duration(_) => DataType::Duration,
+            #[cfg(feature = "decimal")]
+            Self::Decimal(_) => DataType::Decimal,
+            #[cfg(feature = "uuid")]
+            Self::Uuid(_) => DataType::Uuid,
+            #[cfg(feature = "json")]
+            Self::Json(_) => DataType::Json,
+            Self::List(_) => DataType::List,
+        }
+    }
+}
+
+impl<'value, Value: Into<ValueUnion<'value>>> From<Option<Value>> for ValueUnion<'value> {
+    fn from(value: Option<Value>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+impl<'value, Value> TryFrom<ValueUnion<'value>> for Option<Value>
+where
+    Value: TryFrom<ValueUnion<'value>, Error = crate::Error>,
+{
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::Null => Ok(None),
+            _ => Value::try_from(value).map(Some),
         }
     }
 }
@@ -157,6 +212,26 @@ impl<'value> TryFrom<ValueUnion<'value>> for u64 {
     }
 }
 
+impl<'value> From<u128> for ValueUnion<'value> {
+    fn from(value: u128) -> Self {
+        Self::U128(value)
+    }
+}
+
+impl<'value> TryFrom<ValueUnion<'value>> for u128 {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::U128(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::U128,
+            }),
+        }
+    }
+}
+
 impl<'value> From<i8> for ValueUnion<'value> {
     fn from(value: i8) -> Self {
         Self::I8(value)
@@ -237,6 +312,26 @@ impl<'value> TryFrom<ValueUnion<'value>> for i64 {
     }
 }
 
+impl<'value> From<i128> for ValueUnion<'value> {
+    fn from(value: i128) -> Self {
+        Self::I128(value)
+    }
+}
+
+impl<'value> TryFrom<ValueUnion<'value>> for i128 {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::I128(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::I128,
+            }),
+        }
+    }
+}
+
 impl<'value> From<f32> for ValueUnion<'value> {
     fn from(value: f32) -> Self {
         Self::F32(value)
@@ -356,3 +451,169 @@ impl<'value> TryFrom<ValueUnion<'value>> for &'value NaiveDateTime {
         }
     }
 }
+
+impl<'value> From<&'value DateTime<Utc>> for ValueUnion<'value> {
+    fn from(value: &'value DateTime<Utc>) -> Self {
+        Self::DateTimeUtc(value)
+    }
+}
+
+impl<'value> TryFrom<ValueUnion<'value>> for &'value DateTime<Utc> {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::DateTimeUtc(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::DateTimeUtc,
+            }),
+        }
+    }
+}
+
+impl<'value> From<&'value DateTime<FixedOffset>> for ValueUnion<'value> {
+    fn from(value: &'value DateTime<FixedOffset>) -> Self {
+        Self::DateTimeTz(value)
+    }
+}
+
+impl<'value> TryFrom<ValueUnion<'value>> for &'value DateTime<FixedOffset> {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::DateTimeTz(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::DateTimeTz,
+            }),
+        }
+    }
+}
+
+impl<'value> From<&'value NaiveTime> for ValueUnion<'value> {
+    fn from(value: &'value NaiveTime) -> Self {
+        Self::Time(value)
+    }
+}
+
+impl<'value> TryFrom<ValueUnion<'value>> for &'value NaiveTime {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::Time(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::Time,
+            }),
+        }
+    }
+}
+
+impl<'value> From<&'value Duration> for ValueUnion<'value> {
+    fn from(value: &'value Duration) -> Self {
+        Self::Duration(value)
+    }
+}
+
+impl<'value> TryFrom<ValueUnion<'value>> for &'value Duration {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::Duration(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::Duration,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl<'value> From<&'value Decimal> for ValueUnion<'value> {
+    fn from(value: &'value Decimal) -> Self {
+        Self::Decimal(value)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl<'value> TryFrom<ValueUnion<'value>> for &'value Decimal {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::Decimal(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::Decimal,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl<'value> From<&'value Uuid> for ValueUnion<'value> {
+    fn from(value: &'value Uuid) -> Self {
+        Self::Uuid(value)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl<'value> TryFrom<ValueUnion<'value>> for &'value Uuid {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::Uuid(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::Uuid,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'value> From<&'value serde_json::Value> for ValueUnion<'value> {
+    fn from(value: &'value serde_json::Value) -> Self {
+        Self::Json(value)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'value> TryFrom<ValueUnion<'value>> for &'value serde_json::Value {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::Json(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::Json,
+            }),
+        }
+    }
+}
+
+impl<'value> From<&'value [OwnedValueUnion]> for ValueUnion<'value> {
+    fn from(value: &'value [OwnedValueUnion]) -> Self {
+        Self::List(value)
+    }
+}
+
+impl<'value> TryFrom<ValueUnion<'value>> for &'value [OwnedValueUnion] {
+    type Error = crate::Error;
+
+    fn try_from(value: ValueUnion<'value>) -> Result<Self, Self::Error> {
+        match value {
+            ValueUnion::List(value) => Ok(value),
+            _ => Err(crate::Error::ValueCannotBeAccessedAsRequestedType {
+                value_type: value.data_type(),
+                requested_type: DataType::List,
+            }),
+        }
+    }
+}
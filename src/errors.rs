@@ -13,7 +13,9 @@
 // You should have received a copy of the GNU General Public License along with Bedrock ORM. If
 // not, see <https://www.gnu.org/licenses/>.
 
-use crate::domain::DataType;
+use std::time::Duration;
+
+use crate::{database_providers::classify_sqlite_error, domain::DataType};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -27,14 +29,121 @@ pub enum Error {
     },
     #[error("sqlite error: {sqlite_error}")]
     SqliteError { sqlite_error: sqlite::Error },
+    #[error("unique constraint violated: {message}")]
+    UniqueConstraintViolation {
+        constraint: Option<String>,
+        columns: Vec<String>,
+        message: String,
+    },
+    #[error("foreign key constraint violated: {message}")]
+    ForeignKeyConstraintViolation {
+        constraint: Option<String>,
+        message: String,
+    },
+    #[error("not null constraint violated: {message}")]
+    NotNullConstraintViolation {
+        column: Option<String>,
+        message: String,
+    },
+    #[error("check constraint violated: {message}")]
+    CheckConstraintViolation {
+        constraint: Option<String>,
+        message: String,
+    },
+    #[error("database is busy: {message}")]
+    DatabaseBusy { message: String },
+    #[error("database is locked: {message}")]
+    DatabaseLocked { message: String },
+    #[error("database is read-only: {message}")]
+    DatabaseReadOnly { message: String },
+    #[error("schema mismatch: {message}")]
+    SchemaMismatch {
+        object: Option<String>,
+        message: String,
+    },
     #[error("invalid feature name: {feature_name:?}")]
     InvalidFeatureName { feature_name: String },
+    #[error("feature not found: {feature_name:?}")]
+    FeatureNotFound { feature_name: String },
+    #[error("the linked SQLite library was built without thread safety")]
+    SqliteNotThreadSafe,
+    #[error("invalid query: {message}")]
+    InvalidQuery { message: String },
+    #[error("row has {actual} values but {expected} columns were given")]
+    RowLengthMismatch { expected: usize, actual: usize },
+    #[error("invalid bulk insert: {message}")]
+    InvalidBulkInsert { message: String },
+    #[error("invalid pool options: {message}")]
+    InvalidPoolOptions { message: String },
+    #[error("timed out after {timeout:?} waiting for a connection from the pool")]
+    PoolTimedOut { timeout: Duration },
+    #[error("invalid pagination: {message}")]
+    InvalidPagination { message: String },
+    #[error("invalid page cursor: {message}")]
+    InvalidPageCursor { message: String },
+    #[error("query was cancelled")]
+    QueryCancelled,
+    #[error("query timed out")]
+    QueryTimedOut,
+    #[error("unable to serialize value: {message}")]
+    Serialization { message: String },
+    #[error("unable to deserialize value: {message}")]
+    Deserialization { message: String },
+    #[error("integer {value} is out of range for the database")]
+    IntegerOutOfRange { value: String },
+    #[error("invalid value for SQL type: {message}")]
+    InvalidSqlValue { message: String },
+    #[error("unknown value {value} for enum `{enum_name}`")]
+    UnknownEnumValue {
+        enum_name: &'static str,
+        value: String,
+    },
+    #[error("unable to parse {text:?} as `{data_type:?}`")]
+    InvalidValueText { data_type: DataType, text: String },
+    #[error("unknown column type {type_name:?}")]
+    UnknownColumnType { type_name: String },
+    #[error("invalid JSON: {message}")]
+    InvalidJson { message: String },
+    #[error("unexpected query: {message}")]
+    UnexpectedQuery { message: String },
+    #[error("expected queries were never executed: {queries:?}")]
+    UnmetExpectations { queries: Vec<String> },
+    #[error("invalid recording: {message}")]
+    InvalidRecording { message: String },
+    #[error("recorded error: {message}")]
+    RecordedError { message: String },
+    #[error("I/O error: {io_error}")]
+    IoError { io_error: std::io::Error },
+    #[error("connection worker thread has stopped")]
+    ConnectionWorkerStopped,
 }
 
 impl From<sqlite::Error> for Error {
     fn from(value: sqlite::Error) -> Self {
-        Self::SqliteError {
-            sqlite_error: value,
+        classify_sqlite_error(value)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::IoError { io_error: value }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for Error {
+    fn custom<Message: std::fmt::Display>(message: Message) -> Self {
+        Self::Serialization {
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::de::Error for Error {
+    fn custom<Message: std::fmt::Display>(message: Message) -> Self {
+        Self::Deserialization {
+            message: message.to_string(),
         }
     }
 }
@@ -15,8 +15,10 @@
 
 mod errors;
 
+pub mod connection_pooling;
 pub mod database_providers;
 pub mod domain;
+pub mod query_building;
 pub mod query_execution;
 
 pub use errors::{Error, Result};
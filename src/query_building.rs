@@ -0,0 +1,34 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+mod assignment;
+mod create_table_query;
+mod dialect;
+mod expand_list_parameter;
+mod insert_query;
+mod json_expression;
+mod on_conflict;
+mod rendered_query;
+mod sort_order;
+
+pub use assignment::{Assignment, AssignmentValue};
+pub use create_table_query::CreateTableQuery;
+pub use dialect::Dialect;
+pub use expand_list_parameter::expand_list_parameter;
+pub use insert_query::InsertQuery;
+pub use json_expression::JsonExpression;
+pub use on_conflict::OnConflict;
+pub use rendered_query::RenderedQuery;
+pub use sort_order::SortOrder;
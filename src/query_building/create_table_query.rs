@@ -0,0 +1,163 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use crate::domain::{DataType, OwnedValueUnion, SqlEnum, SqlType};
+
+use super::{Dialect, RenderedQuery};
+
+#[derive(Clone, Debug, PartialEq)]
+struct ColumnDefinition {
+    name: String,
+    data_type: DataType,
+    allowed_values: Option<Vec<OwnedValueUnion>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreateTableQuery {
+    table: String,
+    columns: Vec<ColumnDefinition>,
+    primary_key: Vec<String>,
+    if_not_exists: bool,
+}
+
+impl CreateTableQuery {
+    pub fn new(table: &str) -> Self {
+        Self {
+            table: table.to_owned(),
+            columns: Vec::new(),
+            primary_key: Vec::new(),
+            if_not_exists: false,
+        }
+    }
+
+    // Columns are NOT NULL unless their data type is nullable
+    pub fn column(mut self, name: &str, data_type: DataType) -> Self {
+        self.columns.push(ColumnDefinition {
+            name: name.to_owned(),
+            data_type,
+            allowed_values: None,
+        });
+        self
+    }
+
+    pub fn typed_column<Value: SqlType>(self, name: &str) -> Self {
+        self.column(name, Value::data_type())
+    }
+
+    // Adds a CHECK constraint so that the column only holds the enum's values
+    pub fn enum_column<Value: SqlEnum>(mut self, name: &str) -> Self {
+        self.columns.push(ColumnDefinition {
+            name: name.to_owned(),
+            data_type: Value::data_type(),
+            allowed_values: Some(Value::allowed_values()),
+        });
+        self
+    }
+
+    pub fn primary_key<Column: AsRef<str>>(
+        mut self,
+        columns: impl IntoIterator<Item = Column>,
+    ) -> Self {
+        self.primary_key = columns
+            .into_iter()
+            .map(|column| column.as_ref().to_owned())
+            .collect();
+        self
+    }
+
+    pub fn if_not_exists(mut self) -> Self {
+        self.if_not_exists = true;
+        self
+    }
+
+    pub fn render(&self, dialect: Dialect) -> crate::Result<RenderedQuery> {
+        if self.columns.is_empty() {
+            return Err(crate::Error::InvalidQuery {
+                message: "create table queries need at least one column".to_owned(),
+            });
+        }
+
+        let mut definitions = self
+            .columns
+            .iter()
+            .map(|column| {
+                let mut definition = format!(
+                    "{} {}",
+                    dialect.quote_identifier(&column.name),
+                    dialect.type_name(&column.data_type)?
+                );
+
+                if !column.data_type.is_nullable() {
+                    definition.push_str(" NOT NULL");
+                }
+
+                if let Some(allowed_values) = &column.allowed_values {
+                    definition.push_str(&format!(
+                        " CHECK ({} IN ({}))",
+                        dialect.quote_identifier(&column.name),
+                        allowed_values
+                            .iter()
+                            .map(render_literal)
+                            .collect::<crate::Result<Vec<_>>>()?
+                            .join(", ")
+                    ));
+                }
+
+                Ok(definition)
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        if !self.primary_key.is_empty() {
+            definitions.push(format!(
+                "PRIMARY KEY ({})",
+                self.primary_key
+                    .iter()
+                    .map(|column| dialect.quote_identifier(column))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        let text = format!(
+            "CREATE TABLE {}{} ({})",
+            if self.if_not_exists {
+                "IF NOT EXISTS "
+            } else {
+                ""
+            },
+            dialect.quote_identifier(&self.table),
+            definitions.join(", ")
+        );
+
+        Ok(RenderedQuery::new(text, Vec::new()))
+    }
+}
+
+fn render_literal(value: &OwnedValueUnion) -> crate::Result<String> {
+    match value {
+        OwnedValueUnion::String(value) => Ok(format!("'{}'", value.replace('\'', "''"))),
+        OwnedValueUnion::U8(value) => Ok(value.to_string()),
+        OwnedValueUnion::U16(value) => Ok(value.to_string()),
+        OwnedValueUnion::U32(value) => Ok(value.to_string()),
+        OwnedValueUnion::U64(value) => Ok(value.to_string()),
+        OwnedValueUnion::I8(value) => Ok(value.to_string()),
+        OwnedValueUnion::I16(value) => Ok(value.to_string()),
+        OwnedValueUnion::I32(value) => Ok(value.to_string()),
+        OwnedValueUnion::I64(value) => Ok(value.to_string()),
+        _ => Err(crate::Error::InvalidQuery {
+            message: format!("{value:?} cannot be used in a check constraint"),
+        }),
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use crate::domain::DataType;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dialect {
+    Sqlite,
+    Postgres,
+    MySql,
+}
+
+impl Dialect {
+    pub fn quote_identifier(&self, identifier: &str) -> String {
+        match self {
+            Self::Sqlite | Self::Postgres => format!("\"{}\"", identifier.replace('"', "\"\"")),
+            Self::MySql => format!("`{}`", identifier.replace('`', "``")),
+        }
+    }
+
+    // Each part of a name like `table.column` is quoted separately
+    pub fn quote_qualified_identifier(&self, identifier: &str) -> String {
+        identifier
+            .split('.')
+            .map(|part| self.quote_identifier(part))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    // Placeholders are numbered from 1, in the order that parameters appear in the query text
+    pub fn placeholder(&self, parameter: &str, position: usize) -> String {
+        match self {
+            Self::Sqlite => format!(":{parameter}"),
+            Self::Postgres => format!("${position}"),
+            Self::MySql => "?".to_owned(),
+        }
+    }
+
+    pub fn type_name(&self, data_type: &DataType) -> crate::Result<&'static str> {
+        let type_name = match (self, data_type.non_nullable()) {
+            (_, DataType::Null | DataType::List) => {
+                return Err(crate::Error::InvalidQuery {
+                    message: format!("{data_type:?} is not a column type"),
+                })
+            }
+            (_, DataType::Bool) => "BOOLEAN",
+            (Self::Sqlite, DataType::U8 | DataType::U16 | DataType::U32) => "INTEGER",
+            (Self::Sqlite, DataType::I8 | DataType::I16 | DataType::I32 | DataType::I64) => {
+                "INTEGER"
+            }
+            // Integer affinity would turn out-of-range values stored as text into lossy reals
+            (Self::Sqlite, DataType::U64 | DataType::U128 | DataType::I128) => "BLOB",
+            (Self::Postgres, DataType::U8 | DataType::I8 | DataType::I16) => "SMALLINT",
+            (Self::Postgres, DataType::U16 | DataType::I32) => "INTEGER",
+            (Self::Postgres, DataType::U32 | DataType::I64) => "BIGINT",
+            (Self::Postgres, DataType::U64) => "NUMERIC(20)",
+            (Self::Postgres, DataType::U128 | DataType::I128) => "NUMERIC(39)",
+            (Self::MySql, DataType::U8) => "TINYINT UNSIGNED",
+            (Self::MySql, DataType::U16) => "SMALLINT UNSIGNED",
+            (Self::MySql, DataType::U32) => "INT UNSIGNED",
+            (Self::MySql, DataType::U64) => "BIGINT UNSIGNED",
+            (Self::MySql, DataType::I8) => "TINYINT",
+            (Self::MySql, DataType::I16) => "SMALLINT",
+            (Self::MySql, DataType::I32) => "INT",
+            (Self::MySql, DataType::I64) => "BIGINT",
+            (Self::MySql, DataType::U128 | DataType::I128) => "DECIMAL(39)",
+            (Self::Sqlite | Self::Postgres, DataType::F32) => "REAL",
+            (Self::MySql, DataType::F32) => "FLOAT",
+            (Self::Sqlite, DataType::F64) => "REAL",
+            (Self::Postgres, DataType::F64) => "DOUBLE PRECISION",
+            (Self::MySql, DataType::F64) => "DOUBLE",
+            (_, DataType::String) => "TEXT",
+            (Self::Postgres, DataType::Bytestring) => "BYTEA",
+            (_, DataType::Bytestring) => "BLOB",
+            (_, DataType::Date) => "DATE",
+            (Self::Postgres, DataType::DateTime) => "TIMESTAMP",
+            (Self::Postgres, DataType::DateTimeUtc | DataType::DateTimeTz) => "TIMESTAMPTZ",
+            (_, DataType::DateTime | DataType::DateTimeUtc | DataType::DateTimeTz) => "DATETIME",
+            (_, DataType::Time) => "TIME",
+            // Durations are stored as milliseconds, see `duration_to_millis`
+            (Self::Sqlite, DataType::Duration) => "INTEGER",
+            (_, DataType::Duration) => "BIGINT",
+            // Numeric affinity would round decimals that don't fit in a real
+            #[cfg(feature = "decimal")]
+            (Self::Sqlite, DataType::Decimal) => "TEXT",
+            #[cfg(feature = "decimal")]
+            (Self::Postgres, DataType::Decimal) => "NUMERIC",
+            #[cfg(feature = "decimal")]
+            (Self::MySql, DataType::Decimal) => "DECIMAL(65, 30)",
+            // Text affinity keeps either storage form as it was bound
+            #[cfg(feature = "uuid")]
+            (Self::Sqlite, DataType::Uuid) => "TEXT",
+            #[cfg(feature = "uuid")]
+            (Self::Postgres, DataType::Uuid) => "UUID",
+            #[cfg(feature = "uuid")]
+            (Self::MySql, DataType::Uuid) => "CHAR(36)",
+            #[cfg(feature = "json")]
+            (Self::Sqlite, DataType::Json) => "TEXT",
+            #[cfg(feature = "json")]
+            (Self::Postgres, DataType::Json) => "JSONB",
+            #[cfg(feature = "json")]
+            (Self::MySql, DataType::Json) => "JSON",
+            (_, DataType::Nullable(data_type)) => return self.type_name(data_type),
+        };
+
+        Ok(type_name)
+    }
+
+    // The inverse of `type_name`, although several data types can share a type name, so this
+    // can't always give back the data type that was rendered
+    pub fn data_type(&self, type_name: &str) -> crate::Result<DataType> {
+        let normalized = normalize_type_name(type_name);
+
+        if *self == Self::Sqlite {
+            return Ok(sqlite_affinity(&normalized));
+        }
+
+        if let Some(data_type) = common_data_type(&normalized) {
+            return Ok(data_type);
+        }
+
+        let data_type = match (self, normalized.as_str()) {
+            (Self::Postgres, "SMALLINT" | "INT2") => DataType::I16,
+            (Self::Postgres, "INTEGER" | "INT" | "INT4") => DataType::I32,
+            (Self::Postgres, "BIGINT" | "INT8") => DataType::I64,
+            (Self::Postgres, "REAL" | "FLOAT4") => DataType::F32,
+            (Self::Postgres, "DOUBLE PRECISION" | "FLOAT8") => DataType::F64,
+            (Self::Postgres, "BYTEA") => DataType::Bytestring,
+            (Self::Postgres, "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE") => DataType::DateTimeUtc,
+            // MySQL reports BOOLEAN columns as TINYINT(1)
+            (Self::MySql, "TINYINT") if type_arguments(type_name) == "1" => DataType::Bool,
+            (Self::MySql, "TINYINT") => DataType::I8,
+            (Self::MySql, "TINYINT UNSIGNED") => DataType::U8,
+            (Self::MySql, "SMALLINT") => DataType::I16,
+            (Self::MySql, "SMALLINT UNSIGNED") => DataType::U16,
+            (Self::MySql, "MEDIUMINT" | "INT" | "INTEGER") => DataType::I32,
+            (Self::MySql, "MEDIUMINT UNSIGNED" | "INT UNSIGNED" | "INTEGER UNSIGNED") => {
+                DataType::U32
+            }
+            (Self::MySql, "BIGINT") => DataType::I64,
+            (Self::MySql, "BIGINT UNSIGNED") => DataType::U64,
+            (Self::MySql, "FLOAT") => DataType::F32,
+            (Self::MySql, "DOUBLE" | "REAL") => DataType::F64,
+            (Self::MySql, "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" | "SET") => {
+                DataType::String
+            }
+            (
+                Self::MySql,
+                "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB",
+            ) => DataType::Bytestring,
+            _ => {
+                return Err(crate::Error::UnknownColumnType {
+                    type_name: type_name.to_owned(),
+                })
+            }
+        };
+
+        Ok(data_type)
+    }
+}
+
+// Uppercases the name, collapses whitespace and drops size arguments, so `varchar (20)` becomes
+// `VARCHAR` and `int(10) unsigned` becomes `INT UNSIGNED`
+fn normalize_type_name(type_name: &str) -> String {
+    let mut without_arguments = String::with_capacity(type_name.len());
+    let mut depth = 0usize;
+
+    for character in type_name.chars() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => without_arguments.push(character),
+            _ => {}
+        }
+    }
+
+    without_arguments
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase()
+}
+
+// The text between the first pair of parentheses, so `tinyint(1)` gives `1`
+fn type_arguments(type_name: &str) -> &str {
+    type_name
+        .split_once('(')
+        .and_then(|(_, rest)| rest.split_once(')'))
+        .map_or("", |(arguments, _)| arguments.trim())
+}
+
+// Names that mean the same thing in every dialect, including the ones `type_name` renders
+fn common_data_type(normalized: &str) -> Option<DataType> {
+    let data_type = match normalized {
+        "BOOLEAN" | "BOOL" => DataType::Bool,
+        "TEXT" | "VARCHAR" | "CHAR" | "CHARACTER" | "CHARACTER VARYING" => DataType::String,
+        "DATE" => DataType::Date,
+        "DATETIME" | "TIMESTAMP" | "TIMESTAMP WITHOUT TIME ZONE" => DataType::DateTime,
+        "TIME" | "TIME WITHOUT TIME ZONE" => DataType::Time,
+        #[cfg(feature = "decimal")]
+        "DECIMAL" | "NUMERIC" => DataType::Decimal,
+        #[cfg(not(feature = "decimal"))]
+        "DECIMAL" | "NUMERIC" => DataType::F64,
+        #[cfg(feature = "uuid")]
+        "UUID" => DataType::Uuid,
+        #[cfg(feature = "json")]
+        "JSON" | "JSONB" => DataType::Json,
+        _ => return None,
+    };
+
+    Some(data_type)
+}
+
+// SQLite accepts any type name and derives the column's affinity from it, following
+// https://www.sqlite.org/datatype3.html#determination_of_column_affinity
+fn sqlite_affinity(normalized: &str) -> DataType {
+    if normalized.contains("INT") {
+        DataType::I64
+    } else if ["CHAR", "CLOB", "TEXT"]
+        .iter()
+        .any(|pattern| normalized.contains(pattern))
+    {
+        DataType::String
+    } else if normalized.is_empty() || normalized.contains("BLOB") {
+        DataType::Bytestring
+    } else {
+        // Both real and numeric affinity, since numeric columns hold reals as well as integers.
+        // Names like BOOLEAN and DATE get numeric affinity too, so only here do the names that
+        // are recognised in every dialect narrow the data type
+        common_data_type(normalized).unwrap_or(DataType::F64)
+    }
+}
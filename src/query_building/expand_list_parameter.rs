@@ -0,0 +1,45 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use regex::{NoExpand, Regex};
+
+// Replaces `:parameter` with `:parameter_0, :parameter_1, ...` so that a list can be bound one
+// placeholder per element, for example in `WHERE id IN (:ids)`
+pub fn expand_list_parameter(
+    query_text: &str,
+    parameter: &str,
+    length: usize,
+) -> crate::Result<String> {
+    let pattern = Regex::new(&format!(r":{}\b", regex::escape(parameter))).map_err(|error| {
+        crate::Error::InvalidQuery {
+            message: error.to_string(),
+        }
+    })?;
+
+    if !pattern.is_match(query_text) {
+        return Err(crate::Error::InvalidQuery {
+            message: format!("query does not use the parameter {parameter:?}"),
+        });
+    }
+
+    let placeholders = (0..length)
+        .map(|index| format!(":{parameter}_{index}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(pattern
+        .replace_all(query_text, NoExpand(&placeholders))
+        .into_owned())
+}
@@ -0,0 +1,190 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use super::{Assignment, AssignmentValue, Dialect, OnConflict, RenderedQuery};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsertQuery {
+    table: String,
+    columns: Vec<String>,
+    on_conflict: Option<OnConflict>,
+}
+
+impl InsertQuery {
+    pub fn new<Column: AsRef<str>>(table: &str, columns: impl IntoIterator<Item = Column>) -> Self {
+        Self {
+            table: table.to_owned(),
+            columns: collect_strings(columns),
+            on_conflict: None,
+        }
+    }
+
+    pub fn on_conflict_do_nothing<Column: AsRef<str>>(
+        mut self,
+        target: impl IntoIterator<Item = Column>,
+    ) -> Self {
+        self.on_conflict = Some(OnConflict::DoNothing {
+            target: collect_strings(target),
+        });
+        self
+    }
+
+    pub fn on_conflict_do_update<Column: AsRef<str>>(
+        mut self,
+        target: impl IntoIterator<Item = Column>,
+        assignments: impl IntoIterator<Item = Assignment>,
+    ) -> Self {
+        self.on_conflict = Some(OnConflict::DoUpdate {
+            target: collect_strings(target),
+            assignments: assignments.into_iter().collect(),
+        });
+        self
+    }
+
+    pub fn render(&self, dialect: Dialect) -> crate::Result<RenderedQuery> {
+        if self.columns.is_empty() {
+            return Err(crate::Error::InvalidQuery {
+                message: "insert queries need at least one column".to_owned(),
+            });
+        }
+
+        let mut parameters = Vec::new();
+
+        let column_list = self
+            .columns
+            .iter()
+            .map(|column| dialect.quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let values_list = self
+            .columns
+            .iter()
+            .map(|column| push_parameter(dialect, &mut parameters, column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut text = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            dialect.quote_identifier(&self.table),
+            column_list,
+            values_list
+        );
+
+        match (&self.on_conflict, dialect) {
+            (None, _) => {}
+            (Some(OnConflict::DoNothing { target }), Dialect::Sqlite | Dialect::Postgres) => {
+                text.push_str(" ON CONFLICT");
+
+                if !target.is_empty() {
+                    text.push_str(&format!(" ({})", quote_list(dialect, target)));
+                }
+
+                text.push_str(" DO NOTHING");
+            }
+            (Some(OnConflict::DoNothing { target }), Dialect::MySql) => {
+                // MySQL has no direct equivalent, but assigning a column to itself is a no-op that,
+                // unlike INSERT IGNORE, does not also swallow unrelated errors
+                let column = dialect.quote_identifier(target.first().unwrap_or(&self.columns[0]));
+
+                text.push_str(&format!(" ON DUPLICATE KEY UPDATE {column} = {column}"));
+            }
+            (
+                Some(OnConflict::DoUpdate {
+                    target,
+                    assignments,
+                }),
+                _,
+            ) => {
+                if assignments.is_empty() {
+                    return Err(crate::Error::InvalidQuery {
+                        message: "on conflict do update needs at least one assignment".to_owned(),
+                    });
+                }
+
+                if dialect == Dialect::MySql {
+                    // MySQL checks every unique key, so there is no conflict target to render
+                    text.push_str(" ON DUPLICATE KEY UPDATE ");
+                } else {
+                    if target.is_empty() {
+                        return Err(crate::Error::InvalidQuery {
+                            message: "on conflict do update needs a conflict target".to_owned(),
+                        });
+                    }
+
+                    text.push_str(&format!(
+                        " ON CONFLICT ({}) DO UPDATE SET ",
+                        quote_list(dialect, target)
+                    ));
+                }
+
+                let assignment_list = assignments
+                    .iter()
+                    .map(|assignment| {
+                        format!(
+                            "{} = {}",
+                            dialect.quote_identifier(&assignment.column),
+                            render_assignment_value(dialect, &mut parameters, &assignment.value)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                text.push_str(&assignment_list);
+            }
+        }
+
+        Ok(RenderedQuery::new(text, parameters))
+    }
+}
+
+fn collect_strings<Item: AsRef<str>>(items: impl IntoIterator<Item = Item>) -> Vec<String> {
+    items
+        .into_iter()
+        .map(|item| item.as_ref().to_owned())
+        .collect()
+}
+
+fn quote_list(dialect: Dialect, identifiers: &[String]) -> String {
+    identifiers
+        .iter()
+        .map(|identifier| dialect.quote_identifier(identifier))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn push_parameter(dialect: Dialect, parameters: &mut Vec<String>, parameter: &str) -> String {
+    parameters.push(parameter.to_owned());
+    dialect.placeholder(parameter, parameters.len())
+}
+
+fn render_assignment_value(
+    dialect: Dialect,
+    parameters: &mut Vec<String>,
+    value: &AssignmentValue,
+) -> String {
+    match (value, dialect) {
+        (AssignmentValue::Excluded(column), Dialect::Sqlite | Dialect::Postgres) => {
+            format!("excluded.{}", dialect.quote_identifier(column))
+        }
+        (AssignmentValue::Excluded(column), Dialect::MySql) => {
+            format!("VALUES({})", dialect.quote_identifier(column))
+        }
+        (AssignmentValue::Parameter(parameter), _) => {
+            push_parameter(dialect, parameters, parameter)
+        }
+        (AssignmentValue::Expression(expression), _) => expression.clone(),
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use super::Dialect;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonExpression {
+    Extract {
+        column: String,
+        path: String,
+    },
+    Arrow {
+        column: String,
+        path: String,
+    },
+    ArrowText {
+        column: String,
+        path: String,
+    },
+    Each {
+        column: String,
+        path: Option<String>,
+    },
+}
+
+impl JsonExpression {
+    pub fn extract(column: &str, path: &str) -> Self {
+        Self::Extract {
+            column: column.to_owned(),
+            path: path.to_owned(),
+        }
+    }
+
+    // Returns the JSON representation of the value at the path
+    pub fn arrow(column: &str, path: &str) -> Self {
+        Self::Arrow {
+            column: column.to_owned(),
+            path: path.to_owned(),
+        }
+    }
+
+    // Returns the value at the path as a plain SQL value
+    pub fn arrow_text(column: &str, path: &str) -> Self {
+        Self::ArrowText {
+            column: column.to_owned(),
+            path: path.to_owned(),
+        }
+    }
+
+    // A table-valued function, so this belongs in the FROM clause
+    pub fn each(column: &str) -> Self {
+        Self::Each {
+            column: column.to_owned(),
+            path: None,
+        }
+    }
+
+    pub fn each_at(column: &str, path: &str) -> Self {
+        Self::Each {
+            column: column.to_owned(),
+            path: Some(path.to_owned()),
+        }
+    }
+
+    pub fn render(&self, dialect: Dialect) -> crate::Result<String> {
+        match (self, dialect) {
+            (Self::Extract { column, path }, Dialect::Sqlite) => Ok(format!(
+                "json_extract({}, {})",
+                dialect.quote_qualified_identifier(column),
+                path_literal(path)?
+            )),
+            (Self::Extract { column, path }, Dialect::MySql) => Ok(format!(
+                "JSON_EXTRACT({}, {})",
+                dialect.quote_qualified_identifier(column),
+                path_literal(path)?
+            )),
+            (Self::Arrow { column, path }, Dialect::Sqlite | Dialect::MySql) => Ok(format!(
+                "{} -> {}",
+                dialect.quote_qualified_identifier(column),
+                path_literal(path)?
+            )),
+            (Self::ArrowText { column, path }, Dialect::Sqlite | Dialect::MySql) => Ok(format!(
+                "{} ->> {}",
+                dialect.quote_qualified_identifier(column),
+                path_literal(path)?
+            )),
+            (Self::Each { column, path: None }, Dialect::Sqlite) => Ok(format!(
+                "json_each({})",
+                dialect.quote_qualified_identifier(column)
+            )),
+            (
+                Self::Each {
+                    column,
+                    path: Some(path),
+                },
+                Dialect::Sqlite,
+            ) => Ok(format!(
+                "json_each({}, {})",
+                dialect.quote_qualified_identifier(column),
+                path_literal(path)?
+            )),
+            // Postgres uses its own path syntax, and only SQLite has json_each
+            _ => Err(crate::Error::InvalidQuery {
+                message: format!("JSON expression {self:?} is not supported by {dialect:?}"),
+            }),
+        }
+    }
+}
+
+fn path_literal(path: &str) -> crate::Result<String> {
+    if !path.starts_with('$') {
+        return Err(crate::Error::InvalidQuery {
+            message: format!("JSON path {path:?} must start with `$`"),
+        });
+    }
+
+    Ok(format!("'{}'", path.replace('\'', "''")))
+}
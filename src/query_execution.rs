@@ -13,18 +13,56 @@
 // You should have received a copy of the GNU General Public License along with Bedrock ORM. If
 // not, see <https://www.gnu.org/licenses/>.
 
+mod async_execute_query;
+mod async_query_result;
+mod bulk_insert;
+mod cancel_handle;
 mod execute_query;
+mod from_row;
+mod get_dialect;
 mod get_query_result_type;
 mod identify_feature;
 mod inject_features;
+#[cfg(feature = "serde")]
+mod inject_serialized;
+mod keyset;
+mod keyset_page;
+mod list_features;
+mod offset_page;
+mod page_cursor;
+mod paginate;
+mod prepare_query;
 mod query_result;
 mod query_result_type;
+#[cfg(feature = "serde")]
+mod row_deserializer;
+mod row_map;
+mod row_stream;
 mod take_features;
 
+pub use async_execute_query::AsyncExecuteQuery;
+pub use async_query_result::AsyncQueryResult;
+pub use bulk_insert::BulkInsert;
+pub use cancel_handle::CancelHandle;
 pub use execute_query::ExecuteQuery;
+pub use from_row::FromRow;
+pub use get_dialect::GetDialect;
 pub use get_query_result_type::GetQueryResultType;
 pub use identify_feature::IdentifyFeature;
 pub use inject_features::InjectFeatures;
+#[cfg(feature = "serde")]
+pub use inject_serialized::InjectSerialized;
+pub use keyset::Keyset;
+pub use keyset_page::KeysetPage;
+pub use list_features::ListFeatures;
+pub use offset_page::OffsetPage;
+pub use page_cursor::PageCursor;
+pub use paginate::Paginate;
+pub use prepare_query::PrepareQuery;
 pub use query_result::QueryResult;
 pub use query_result_type::QueryResultType;
+#[cfg(feature = "serde")]
+pub use row_deserializer::{from_row, Deserialized, RowDeserializer};
+pub use row_map::RowMap;
+pub use row_stream::RowStream;
 pub use take_features::TakeFeatures;
@@ -0,0 +1,69 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::future::Future;
+
+use futures::Stream;
+
+use crate::Result;
+
+use super::{AsyncQueryResult, GetQueryResultType, InjectFeatures, QueryResultType, TakeFeatures};
+
+pub trait AsyncExecuteQuery {
+    type Query: InjectFeatures + GetQueryResultType;
+    type Row: TakeFeatures;
+    type RowStream: Stream<Item = Self::Row>;
+
+    fn execute_without_results(
+        &self,
+        query: &mut Self::Query,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    fn execute_with_change_count(
+        &self,
+        query: &mut Self::Query,
+    ) -> impl Future<Output = Result<usize>> + Send;
+
+    fn execute_with_iterator(
+        &self,
+        query: &mut Self::Query,
+    ) -> impl Future<Output = Result<Self::RowStream>> + Send;
+
+    fn execute(
+        &self,
+        query: &mut Self::Query,
+    ) -> impl Future<Output = Result<AsyncQueryResult<Self::Row, Self::RowStream>>> + Send
+    where
+        Self: Sync,
+        Self::Query: Send,
+    {
+        async move {
+            match query.query_result_type() {
+                QueryResultType::None => {
+                    self.execute_without_results(query).await?;
+                    Ok(AsyncQueryResult::None)
+                }
+                QueryResultType::ChangeCount => {
+                    let count = self.execute_with_change_count(query).await?;
+                    Ok(AsyncQueryResult::ChangeCount { count })
+                }
+                QueryResultType::Iterator => {
+                    let row_stream = self.execute_with_iterator(query).await?;
+                    Ok(AsyncQueryResult::Stream { row_stream })
+                }
+            }
+        }
+    }
+}
@@ -15,12 +15,14 @@
 
 use crate::Result;
 
-use super::{GetQueryResultType, InjectFeatures, QueryResult, QueryResultType, TakeFeatures};
+use super::{
+    GetQueryResultType, InjectFeatures, QueryResult, QueryResultType, RowStream, TakeFeatures,
+};
 
 pub trait ExecuteQuery {
     type Query: InjectFeatures + GetQueryResultType;
     type Row: TakeFeatures;
-    type RowIterator<'query>: Iterator<Item = Self::Row>
+    type RowIterator<'query>: RowStream<Item = Self::Row>
     where
         Self: 'query;
 
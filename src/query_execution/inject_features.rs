@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU General Public License along with Bedrock ORM. If
 // not, see <https://www.gnu.org/licenses/>.
 
-use crate::domain::ValueUnion;
+use crate::domain::{SqlType, ValueUnion};
 
 use super::IdentifyFeature;
 
@@ -25,4 +25,22 @@ pub trait InjectFeatures {
         identifier: &Self::Identifier,
         value: &ValueUnion,
     ) -> crate::Result<()>;
+
+    fn inject_sql_value<Value: SqlType>(
+        &mut self,
+        identifier: &Self::Identifier,
+        value: &Value,
+    ) -> crate::Result<()> {
+        self.inject_feature(identifier, &value.to_sql_value().as_value_union())
+    }
+
+    fn inject_sql_values<Value: SqlType>(
+        &mut self,
+        identifier: &Self::Identifier,
+        values: &[Value],
+    ) -> crate::Result<()> {
+        let values = values.iter().map(SqlType::to_sql_value).collect::<Vec<_>>();
+
+        self.inject_feature(identifier, &ValueUnion::List(&values))
+    }
 }
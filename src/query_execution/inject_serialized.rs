@@ -0,0 +1,495 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use serde::{
+    ser::{self, Impossible, SerializeMap, SerializeSeq, SerializeStruct},
+    Serialize,
+};
+
+use crate::{domain::OwnedValueUnion, Error, Result};
+
+use super::InjectFeatures;
+
+pub trait InjectSerialized: InjectFeatures<Identifier = String> {
+    fn inject_serialized<Parameters: Serialize + ?Sized>(
+        &mut self,
+        parameters: &Parameters,
+    ) -> Result<()>;
+}
+
+impl<Query> InjectSerialized for Query
+where
+    Query: InjectFeatures<Identifier = String>,
+{
+    fn inject_serialized<Parameters: Serialize + ?Sized>(
+        &mut self,
+        parameters: &Parameters,
+    ) -> Result<()> {
+        parameters.serialize(ParametersSerializer {
+            query: self,
+            current_key: None,
+        })
+    }
+}
+
+fn unsupported(kind: &str) -> Error {
+    Error::Serialization {
+        message: format!("{kind} cannot be bound as a parameter"),
+    }
+}
+
+struct ParametersSerializer<'query, Query> {
+    query: &'query mut Query,
+    current_key: Option<String>,
+}
+
+impl<'query, Query> ParametersSerializer<'query, Query>
+where
+    Query: InjectFeatures<Identifier = String>,
+{
+    fn inject<Value: Serialize + ?Sized>(&mut self, name: String, value: &Value) -> Result<()> {
+        self.query
+            .inject_feature(&name, &value.serialize(ValueSerializer)?.as_value_union())
+    }
+}
+
+impl<'query, Query> ser::Serializer for ParametersSerializer<'query, Query>
+where
+    Query: InjectFeatures<Identifier = String>,
+{
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_bool(self, _value: bool) -> Result<()> {
+        Err(unsupported("a bare bool"))
+    }
+
+    fn serialize_i8(self, _value: i8) -> Result<()> {
+        Err(unsupported("a bare integer"))
+    }
+
+    fn serialize_i16(self, _value: i16) -> Result<()> {
+        Err(unsupported("a bare integer"))
+    }
+
+    fn serialize_i32(self, _value: i32) -> Result<()> {
+        Err(unsupported("a bare integer"))
+    }
+
+    fn serialize_i64(self, _value: i64) -> Result<()> {
+        Err(unsupported("a bare integer"))
+    }
+
+    fn serialize_u8(self, _value: u8) -> Result<()> {
+        Err(unsupported("a bare integer"))
+    }
+
+    fn serialize_u16(self, _value: u16) -> Result<()> {
+        Err(unsupported("a bare integer"))
+    }
+
+    fn serialize_u32(self, _value: u32) -> Result<()> {
+        Err(unsupported("a bare integer"))
+    }
+
+    fn serialize_u64(self, _value: u64) -> Result<()> {
+        Err(unsupported("a bare integer"))
+    }
+
+    fn serialize_f32(self, _value: f32) -> Result<()> {
+        Err(unsupported("a bare float"))
+    }
+
+    fn serialize_f64(self, _value: f64) -> Result<()> {
+        Err(unsupported("a bare float"))
+    }
+
+    fn serialize_char(self, _value: char) -> Result<()> {
+        Err(unsupported("a bare char"))
+    }
+
+    fn serialize_str(self, _value: &str) -> Result<()> {
+        Err(unsupported("a bare string"))
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<()> {
+        Err(unsupported("bare bytes"))
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_some<Value: Serialize + ?Sized>(self, value: &Value) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<()> {
+        Err(unsupported("an enum"))
+    }
+
+    fn serialize_newtype_struct<Value: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &Value,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<Value: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &Value,
+    ) -> Result<()> {
+        Err(unsupported("an enum"))
+    }
+
+    fn serialize_seq(self, _length: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(unsupported("a sequence"))
+    }
+
+    fn serialize_tuple(self, _length: usize) -> Result<Self::SerializeTuple> {
+        Err(unsupported("a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _length: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported("a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _length: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("an enum"))
+    }
+
+    fn serialize_map(self, _length: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _length: usize,
+    ) -> Result<Self::SerializeStruct> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _length: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported("an enum"))
+    }
+}
+
+impl<'query, Query> SerializeStruct for ParametersSerializer<'query, Query>
+where
+    Query: InjectFeatures<Identifier = String>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<Value: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &Value,
+    ) -> Result<()> {
+        self.inject(key.to_owned(), value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'query, Query> SerializeMap for ParametersSerializer<'query, Query>
+where
+    Query: InjectFeatures<Identifier = String>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<Key: Serialize + ?Sized>(&mut self, key: &Key) -> Result<()> {
+        match key.serialize(ValueSerializer)? {
+            OwnedValueUnion::String(key) => {
+                self.current_key = Some(key);
+                Ok(())
+            }
+            _ => Err(unsupported("a map with non-string keys")),
+        }
+    }
+
+    fn serialize_value<Value: Serialize + ?Sized>(&mut self, value: &Value) -> Result<()> {
+        let key = self
+            .current_key
+            .take()
+            .ok_or_else(|| Error::Serialization {
+                message: "map value serialized before its key".to_owned(),
+            })?;
+
+        self.inject(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = OwnedValueUnion;
+    type Error = Error;
+    type SerializeSeq = SequenceSerializer;
+    type SerializeTuple = Impossible<Self::Ok, Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Error>;
+    type SerializeMap = Impossible<Self::Ok, Error>;
+    type SerializeStruct = Impossible<Self::Ok, Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, Error>;
+
+    fn serialize_bool(self, value: bool) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::Bool(value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::I8(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::I16(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::I32(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::I64(value))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::I128(value))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::U8(value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::U16(value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::U32(value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::U64(value))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::U128(value))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::F32(value))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::F64(value))
+    }
+
+    fn serialize_char(self, value: char) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::String(value.to_string()))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::String(value.to_owned()))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::Bytestring(value.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::Null)
+    }
+
+    fn serialize_some<Value: Serialize + ?Sized>(self, value: &Value) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::Null)
+    }
+
+    // Unit variants are stored by name, which is also how the row deserializer reads them back
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok> {
+        Ok(OwnedValueUnion::String(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<Value: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &Value,
+    ) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<Value: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &Value,
+    ) -> Result<Self::Ok> {
+        Err(unsupported("an enum variant with data"))
+    }
+
+    fn serialize_seq(self, length: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(SequenceSerializer {
+            values: Vec::with_capacity(length.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, _length: usize) -> Result<Self::SerializeTuple> {
+        Err(unsupported("a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _length: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported("a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _length: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("an enum variant with data"))
+    }
+
+    fn serialize_map(self, _length: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(unsupported("a nested map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _length: usize,
+    ) -> Result<Self::SerializeStruct> {
+        Err(unsupported("a nested struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _length: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported("an enum variant with data"))
+    }
+}
+
+// Fields like `Vec<u8>` serialize as sequences of bytes, which are bound as blobs, while other
+// sequences are bound as lists. An empty sequence has no element type to go by, so it is bound as
+// an empty list
+struct SequenceSerializer {
+    values: Vec<OwnedValueUnion>,
+}
+
+impl SerializeSeq for SequenceSerializer {
+    type Ok = OwnedValueUnion;
+    type Error = Error;
+
+    fn serialize_element<Value: Serialize + ?Sized>(&mut self, value: &Value) -> Result<()> {
+        match value.serialize(ValueSerializer)? {
+            OwnedValueUnion::List(_) => Err(unsupported("a nested sequence")),
+            value => {
+                self.values.push(value);
+                Ok(())
+            }
+        }
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        if self.values.is_empty() {
+            return Ok(OwnedValueUnion::List(self.values));
+        }
+
+        let bytes = self
+            .values
+            .iter()
+            .map(|value| match value {
+                OwnedValueUnion::U8(byte) => Some(*byte),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+
+        Ok(match bytes {
+            Some(bytes) => OwnedValueUnion::Bytestring(bytes),
+            None => OwnedValueUnion::List(self.values),
+        })
+    }
+}
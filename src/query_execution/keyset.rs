@@ -0,0 +1,42 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use crate::query_building::SortOrder;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyset {
+    pub columns: Vec<String>,
+    pub order: SortOrder,
+}
+
+impl Keyset {
+    pub fn new<Column: Into<String>>(
+        columns: impl IntoIterator<Item = Column>,
+        order: SortOrder,
+    ) -> Self {
+        Self {
+            columns: columns.into_iter().map(Into::into).collect(),
+            order,
+        }
+    }
+
+    pub fn ascending<Column: Into<String>>(columns: impl IntoIterator<Item = Column>) -> Self {
+        Self::new(columns, SortOrder::Ascending)
+    }
+
+    pub fn descending<Column: Into<String>>(columns: impl IntoIterator<Item = Column>) -> Self {
+        Self::new(columns, SortOrder::Descending)
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use super::PageCursor;
+
+#[derive(Debug)]
+pub struct KeysetPage<Row> {
+    pub rows: Vec<Row>,
+    pub next_cursor: Option<PageCursor>,
+}
+
+impl<Row> KeysetPage<Row> {
+    pub fn has_next_page(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use crate::domain::ValueUnion;
+
+use super::TakeFeatures;
+
+// Features are listed in order, and can be read by their position in that list when several of them
+// share an identifier, such as the columns of a join
+pub trait ListFeatures: TakeFeatures {
+    fn feature_identifiers(&self) -> crate::Result<Vec<Self::Identifier>>;
+
+    fn take_feature_at(&self, index: usize) -> crate::Result<Option<ValueUnion<'_>>>;
+}
@@ -0,0 +1,341 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, Timelike, Utc,
+};
+use std::time::Duration;
+
+use crate::{domain::OwnedValueUnion, Error, Result};
+
+const CURSOR_VERSION: u8 = 1;
+// Bounds the recursion when reading nested lists from untrusted tokens
+const MAX_LIST_DEPTH: usize = 32;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageCursor {
+    values: Vec<OwnedValueUnion>,
+}
+
+impl PageCursor {
+    pub fn new(values: Vec<OwnedValueUnion>) -> Self {
+        Self { values }
+    }
+
+    pub fn values(&self) -> &[OwnedValueUnion] {
+        &self.values
+    }
+
+    pub fn to_token(&self) -> String {
+        let mut bytes = vec![CURSOR_VERSION];
+
+        for value in &self.values {
+            encode_value(&mut bytes, value);
+        }
+
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    pub fn from_token(token: &str) -> Result<Self> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(token)
+            .map_err(|_| invalid_cursor("token is not valid base64"))?;
+
+        let mut reader = CursorReader { bytes: &bytes };
+
+        if reader.take_u8()? != CURSOR_VERSION {
+            return Err(invalid_cursor("unsupported cursor version"));
+        }
+
+        let mut values = Vec::new();
+
+        while !reader.bytes.is_empty() {
+            values.push(reader.take_value(0)?);
+        }
+
+        Ok(Self { values })
+    }
+}
+
+impl fmt::Display for PageCursor {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.to_token())
+    }
+}
+
+fn encode_value(bytes: &mut Vec<u8>, value: &OwnedValueUnion) {
+    match value {
+        OwnedValueUnion::Bool(value) => {
+            bytes.push(0);
+            bytes.push(*value as u8);
+        }
+        OwnedValueUnion::U8(value) => {
+            bytes.push(1);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::U16(value) => {
+            bytes.push(2);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::U32(value) => {
+            bytes.push(3);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::U64(value) => {
+            bytes.push(4);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::I8(value) => {
+            bytes.push(5);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::I16(value) => {
+            bytes.push(6);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::I32(value) => {
+            bytes.push(7);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::I64(value) => {
+            bytes.push(8);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::F32(value) => {
+            bytes.push(9);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::F64(value) => {
+            bytes.push(10);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::String(value) => {
+            bytes.push(11);
+            encode_length_prefixed(bytes, value.as_bytes());
+        }
+        OwnedValueUnion::Bytestring(value) => {
+            bytes.push(12);
+            encode_length_prefixed(bytes, value);
+        }
+        OwnedValueUnion::Date(value) => {
+            bytes.push(13);
+            bytes.extend(value.num_days_from_ce().to_be_bytes());
+        }
+        OwnedValueUnion::DateTime(value) => {
+            bytes.push(14);
+            bytes.extend(value.timestamp().to_be_bytes());
+            bytes.extend(value.timestamp_subsec_nanos().to_be_bytes());
+        }
+        OwnedValueUnion::DateTimeUtc(value) => {
+            bytes.push(15);
+            bytes.extend(value.timestamp().to_be_bytes());
+            bytes.extend(value.timestamp_subsec_nanos().to_be_bytes());
+        }
+        OwnedValueUnion::DateTimeTz(value) => {
+            bytes.push(16);
+            bytes.extend(value.timestamp().to_be_bytes());
+            bytes.extend(value.timestamp_subsec_nanos().to_be_bytes());
+            bytes.extend(value.offset().fix().local_minus_utc().to_be_bytes());
+        }
+        OwnedValueUnion::Time(value) => {
+            bytes.push(17);
+            bytes.extend(value.num_seconds_from_midnight().to_be_bytes());
+            bytes.extend(value.nanosecond().to_be_bytes());
+        }
+        OwnedValueUnion::Duration(value) => {
+            bytes.push(18);
+            bytes.extend(value.as_secs().to_be_bytes());
+            bytes.extend(value.subsec_nanos().to_be_bytes());
+        }
+        #[cfg(feature = "decimal")]
+        OwnedValueUnion::Decimal(value) => {
+            bytes.push(19);
+            bytes.extend(value.serialize());
+        }
+        #[cfg(feature = "uuid")]
+        OwnedValueUnion::Uuid(value) => {
+            bytes.push(20);
+            bytes.extend(value.as_bytes());
+        }
+        #[cfg(feature = "json")]
+        OwnedValueUnion::Json(value) => {
+            bytes.push(21);
+            encode_length_prefixed(bytes, value.to_string().as_bytes());
+        }
+        OwnedValueUnion::Null => bytes.push(24),
+        OwnedValueUnion::List(values) => {
+            bytes.push(25);
+            bytes.extend((values.len() as u32).to_be_bytes());
+
+            for value in values {
+                encode_value(bytes, value);
+            }
+        }
+        OwnedValueUnion::U128(value) => {
+            bytes.push(22);
+            bytes.extend(value.to_be_bytes());
+        }
+        OwnedValueUnion::I128(value) => {
+            bytes.push(23);
+            bytes.extend(value.to_be_bytes());
+        }
+    }
+}
+
+fn encode_length_prefixed(bytes: &mut Vec<u8>, value: &[u8]) {
+    bytes.extend((value.len() as u32).to_be_bytes());
+    bytes.extend(value);
+}
+
+struct CursorReader<'bytes> {
+    bytes: &'bytes [u8],
+}
+
+impl<'bytes> CursorReader<'bytes> {
+    fn take(&mut self, length: usize) -> Result<&'bytes [u8]> {
+        if self.bytes.len() < length {
+            return Err(invalid_cursor("token is truncated"));
+        }
+
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const LENGTH: usize>(&mut self) -> Result<[u8; LENGTH]> {
+        Ok(self
+            .take(LENGTH)?
+            .try_into()
+            .expect("slice has the requested length"))
+    }
+
+    fn take_u8(&mut self) -> Result<u8> {
+        Ok(self.take_array::<1>()?[0])
+    }
+
+    fn take_length_prefixed(&mut self) -> Result<&'bytes [u8]> {
+        let length = u32::from_be_bytes(self.take_array()?) as usize;
+        self.take(length)
+    }
+
+    fn take_utc_datetime(&mut self) -> Result<DateTime<Utc>> {
+        let seconds = i64::from_be_bytes(self.take_array()?);
+        let nanoseconds = u32::from_be_bytes(self.take_array()?);
+
+        NaiveDateTime::from_timestamp_opt(seconds, nanoseconds)
+            .map(|value| DateTime::<Utc>::from_utc(value, Utc))
+            .ok_or_else(|| invalid_cursor("datetime value is out of range"))
+    }
+
+    fn take_value(&mut self, depth: usize) -> Result<OwnedValueUnion> {
+        Ok(match self.take_u8()? {
+            0 => match self.take_u8()? {
+                0 => OwnedValueUnion::Bool(false),
+                1 => OwnedValueUnion::Bool(true),
+                _ => return Err(invalid_cursor("invalid boolean value")),
+            },
+            1 => OwnedValueUnion::U8(u8::from_be_bytes(self.take_array()?)),
+            2 => OwnedValueUnion::U16(u16::from_be_bytes(self.take_array()?)),
+            3 => OwnedValueUnion::U32(u32::from_be_bytes(self.take_array()?)),
+            4 => OwnedValueUnion::U64(u64::from_be_bytes(self.take_array()?)),
+            5 => OwnedValueUnion::I8(i8::from_be_bytes(self.take_array()?)),
+            6 => OwnedValueUnion::I16(i16::from_be_bytes(self.take_array()?)),
+            7 => OwnedValueUnion::I32(i32::from_be_bytes(self.take_array()?)),
+            8 => OwnedValueUnion::I64(i64::from_be_bytes(self.take_array()?)),
+            9 => OwnedValueUnion::F32(f32::from_be_bytes(self.take_array()?)),
+            10 => OwnedValueUnion::F64(f64::from_be_bytes(self.take_array()?)),
+            11 => OwnedValueUnion::String(
+                String::from_utf8(self.take_length_prefixed()?.to_vec())
+                    .map_err(|_| invalid_cursor("invalid UTF-8 in string value"))?,
+            ),
+            12 => OwnedValueUnion::Bytestring(self.take_length_prefixed()?.to_vec()),
+            13 => OwnedValueUnion::Date(
+                NaiveDate::from_num_days_from_ce_opt(i32::from_be_bytes(self.take_array()?))
+                    .ok_or_else(|| invalid_cursor("date value is out of range"))?,
+            ),
+            14 => {
+                let seconds = i64::from_be_bytes(self.take_array()?);
+                let nanoseconds = u32::from_be_bytes(self.take_array()?);
+
+                OwnedValueUnion::DateTime(
+                    NaiveDateTime::from_timestamp_opt(seconds, nanoseconds)
+                        .ok_or_else(|| invalid_cursor("datetime value is out of range"))?,
+                )
+            }
+            15 => OwnedValueUnion::DateTimeUtc(self.take_utc_datetime()?),
+            16 => {
+                let value = self.take_utc_datetime()?;
+                let offset = FixedOffset::east_opt(i32::from_be_bytes(self.take_array()?))
+                    .ok_or_else(|| invalid_cursor("offset is out of range"))?;
+
+                OwnedValueUnion::DateTimeTz(value.with_timezone(&offset))
+            }
+            17 => {
+                let seconds = u32::from_be_bytes(self.take_array()?);
+                let nanoseconds = u32::from_be_bytes(self.take_array()?);
+
+                OwnedValueUnion::Time(
+                    NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanoseconds)
+                        .ok_or_else(|| invalid_cursor("time value is out of range"))?,
+                )
+            }
+            18 => {
+                let seconds = u64::from_be_bytes(self.take_array()?);
+                let nanoseconds = u32::from_be_bytes(self.take_array()?);
+
+                if nanoseconds >= 1_000_000_000 {
+                    return Err(invalid_cursor("duration value is out of range"));
+                }
+
+                OwnedValueUnion::Duration(Duration::new(seconds, nanoseconds))
+            }
+            #[cfg(feature = "decimal")]
+            19 => OwnedValueUnion::Decimal(rust_decimal::Decimal::deserialize(self.take_array()?)),
+            #[cfg(feature = "uuid")]
+            20 => OwnedValueUnion::Uuid(uuid::Uuid::from_bytes(self.take_array()?)),
+            #[cfg(feature = "json")]
+            21 => OwnedValueUnion::Json(
+                serde_json::from_slice(self.take_length_prefixed()?)
+                    .map_err(|_| invalid_cursor("invalid JSON value"))?,
+            ),
+            22 => OwnedValueUnion::U128(u128::from_be_bytes(self.take_array()?)),
+            23 => OwnedValueUnion::I128(i128::from_be_bytes(self.take_array()?)),
+            24 => OwnedValueUnion::Null,
+            25 => {
+                if depth == MAX_LIST_DEPTH {
+                    return Err(invalid_cursor("lists are nested too deeply"));
+                }
+
+                let length = u32::from_be_bytes(self.take_array()?);
+
+                OwnedValueUnion::List(
+                    (0..length)
+                        .map(|_| self.take_value(depth + 1))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            _ => return Err(invalid_cursor("unknown value tag")),
+        })
+    }
+}
+
+fn invalid_cursor(message: &str) -> Error {
+    Error::InvalidPageCursor {
+        message: message.to_owned(),
+    }
+}
@@ -0,0 +1,315 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{
+    domain::OwnedValueUnion,
+    query_building::{Dialect, SortOrder},
+    Error, Result,
+};
+
+use super::{
+    ExecuteQuery, GetDialect, InjectFeatures, Keyset, KeysetPage, OffsetPage, PageCursor,
+    PrepareQuery, QueryResultType, TakeFeatures,
+};
+
+const TOTAL_COUNT_COLUMN: &str = "bedrock_orm_total_count";
+const KEY_PARAMETER_PREFIX: &str = "bedrock_orm_page_key_";
+
+lazy_static! {
+    static ref NUMBERED_PLACEHOLDER_REGEX: Regex = Regex::new(r"\$([0-9]+)").unwrap();
+}
+
+pub trait Paginate: ExecuteQuery {
+    fn fetch_offset_page<Bind>(
+        &self,
+        query_text: &str,
+        bind: Bind,
+        order_by: &[(&str, SortOrder)],
+        offset: usize,
+        limit: usize,
+    ) -> Result<OffsetPage<Self::Row>>
+    where
+        Bind: Fn(&mut Self::Query) -> Result<()>;
+
+    fn fetch_keyset_page<Bind>(
+        &self,
+        query_text: &str,
+        bind: Bind,
+        keyset: &Keyset,
+        after: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<KeysetPage<Self::Row>>
+    where
+        Bind: Fn(&mut Self::Query) -> Result<()>;
+}
+
+impl<Connection> Paginate for Connection
+where
+    Connection: ExecuteQuery + PrepareQuery + GetDialect,
+    Connection::Query: InjectFeatures<Identifier = String>,
+    Connection::Row: TakeFeatures<Identifier = String>,
+{
+    fn fetch_offset_page<Bind>(
+        &self,
+        query_text: &str,
+        bind: Bind,
+        order_by: &[(&str, SortOrder)],
+        offset: usize,
+        limit: usize,
+    ) -> Result<OffsetPage<Self::Row>>
+    where
+        Bind: Fn(&mut Self::Query) -> Result<()>,
+    {
+        validate_limit(limit)?;
+
+        if order_by.is_empty() {
+            return Err(Error::InvalidPagination {
+                message: "offset pages must be ordered by at least one column".to_owned(),
+            });
+        }
+
+        let inner_query_text = trim_query_text(query_text);
+
+        let mut count_query = self.prepare_query(
+            &format!(
+                "SELECT COUNT(*) AS {TOTAL_COUNT_COLUMN} FROM ({inner_query_text}) AS paginated"
+            ),
+            QueryResultType::Iterator,
+        )?;
+        bind(&mut count_query)?;
+        let total_count = take_total_count(self, &mut count_query)?;
+
+        // An ORDER BY inside the derived table is not guaranteed to survive it, so the order
+        // that decides which rows land on which page is applied to the outer query
+        let mut page_query = self.prepare_query(
+            &format!(
+                "SELECT * FROM ({inner_query_text}) AS paginated ORDER BY {} LIMIT {limit} \
+                 OFFSET {offset}",
+                render_order_by(self.dialect(), order_by.iter().copied())
+            ),
+            QueryResultType::Iterator,
+        )?;
+        bind(&mut page_query)?;
+        let rows = self.execute_with_iterator(&mut page_query)?.collect();
+
+        Ok(OffsetPage {
+            rows,
+            offset,
+            limit,
+            total_count,
+        })
+    }
+
+    fn fetch_keyset_page<Bind>(
+        &self,
+        query_text: &str,
+        bind: Bind,
+        keyset: &Keyset,
+        after: Option<&PageCursor>,
+        limit: usize,
+    ) -> Result<KeysetPage<Self::Row>>
+    where
+        Bind: Fn(&mut Self::Query) -> Result<()>,
+    {
+        validate_limit(limit)?;
+
+        if keyset.columns.is_empty() {
+            return Err(Error::InvalidPagination {
+                message: "keyset must have at least one column".to_owned(),
+            });
+        }
+
+        if let Some(after) = after {
+            if after.values().len() != keyset.columns.len() {
+                return Err(Error::InvalidPageCursor {
+                    message: format!(
+                        "cursor has {} values but the keyset has {} columns",
+                        after.values().len(),
+                        keyset.columns.len()
+                    ),
+                });
+            }
+        }
+
+        let mut page_query = self.prepare_query(
+            &render_keyset_query(
+                self.dialect(),
+                trim_query_text(query_text),
+                keyset,
+                after.is_some(),
+                limit,
+            ),
+            QueryResultType::Iterator,
+        )?;
+        bind(&mut page_query)?;
+
+        if let Some(after) = after {
+            for (index, value) in after.values().iter().enumerate() {
+                page_query.inject_feature(
+                    &format!("{KEY_PARAMETER_PREFIX}{index}"),
+                    &value.as_value_union(),
+                )?;
+            }
+        }
+
+        // One extra row is fetched to find out whether there is a next page
+        let mut rows: Vec<Self::Row> = self
+            .execute_with_iterator(&mut page_query)?
+            .take(limit + 1)
+            .collect();
+
+        let next_cursor = if rows.len() > limit {
+            rows.truncate(limit);
+            Some(take_cursor(&rows[limit - 1], keyset)?)
+        } else {
+            None
+        };
+
+        Ok(KeysetPage { rows, next_cursor })
+    }
+}
+
+fn validate_limit(limit: usize) -> Result<()> {
+    if limit == 0 {
+        return Err(Error::InvalidPagination {
+            message: "page limit must be greater than zero".to_owned(),
+        });
+    }
+
+    Ok(())
+}
+
+fn trim_query_text(query_text: &str) -> &str {
+    query_text.trim().trim_end_matches(';').trim_end()
+}
+
+fn render_keyset_query(
+    dialect: Dialect,
+    inner_query_text: &str,
+    keyset: &Keyset,
+    has_cursor: bool,
+    limit: usize,
+) -> String {
+    let mut query_text = format!("SELECT * FROM ({inner_query_text}) AS paginated");
+
+    if has_cursor {
+        let columns = keyset
+            .columns
+            .iter()
+            .map(|column| dialect.quote_identifier(column))
+            .collect::<Vec<_>>();
+
+        // The key parameters come after any in the inner query, which matters for dialects that
+        // number their placeholders
+        let first_position = highest_numbered_placeholder(inner_query_text) + 1;
+        let parameters = (0..columns.len())
+            .map(|index| {
+                dialect.placeholder(
+                    &format!("{KEY_PARAMETER_PREFIX}{index}"),
+                    first_position + index,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let comparison = match keyset.order {
+            SortOrder::Ascending => ">",
+            SortOrder::Descending => "<",
+        };
+
+        query_text.push_str(&format!(
+            " WHERE ({}) {comparison} ({})",
+            columns.join(", "),
+            parameters.join(", ")
+        ));
+    }
+
+    query_text.push_str(&format!(
+        " ORDER BY {} LIMIT {}",
+        render_order_by(
+            dialect,
+            keyset
+                .columns
+                .iter()
+                .map(|column| (column.as_str(), keyset.order))
+        ),
+        limit + 1
+    ));
+
+    query_text
+}
+
+fn render_order_by<'column>(
+    dialect: Dialect,
+    order_by: impl Iterator<Item = (&'column str, SortOrder)>,
+) -> String {
+    order_by
+        .map(|(column, order)| format!("{} {}", dialect.quote_identifier(column), order.keyword()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn highest_numbered_placeholder(query_text: &str) -> usize {
+    NUMBERED_PLACEHOLDER_REGEX
+        .captures_iter(query_text)
+        .filter_map(|captures| captures[1].parse().ok())
+        .max()
+        .unwrap_or(0)
+}
+
+fn take_total_count<Connection>(
+    connection: &Connection,
+    query: &mut Connection::Query,
+) -> Result<usize>
+where
+    Connection: ExecuteQuery,
+    Connection::Row: TakeFeatures<Identifier = String>,
+{
+    let row = connection
+        .execute_with_iterator(query)?
+        .next()
+        .ok_or_else(|| Error::InvalidPagination {
+            message: "count query returned no rows".to_owned(),
+        })?;
+
+    let total_count = row
+        .take_feature(&TOTAL_COUNT_COLUMN.to_owned())?
+        .ok_or_else(|| Error::InvalidPagination {
+            message: "count query returned null".to_owned(),
+        })?;
+
+    Ok(i64::try_from(total_count)? as usize)
+}
+
+fn take_cursor<Row>(row: &Row, keyset: &Keyset) -> Result<PageCursor>
+where
+    Row: TakeFeatures<Identifier = String>,
+{
+    let mut values = Vec::with_capacity(keyset.columns.len());
+
+    for column in &keyset.columns {
+        let value = row
+            .take_feature(column)?
+            .ok_or_else(|| Error::InvalidPagination {
+                message: format!("keyset column {column:?} is null"),
+            })?;
+
+        values.push(OwnedValueUnion::from(value));
+    }
+
+    Ok(PageCursor::new(values))
+}
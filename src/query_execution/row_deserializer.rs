@@ -0,0 +1,304 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use serde::{
+    de::{
+        self, value::SeqDeserializer, DeserializeOwned, DeserializeSeed, IntoDeserializer,
+        MapAccess, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize,
+};
+
+use crate::{
+    domain::{duration_to_millis, SqlType, ValueUnion},
+    Error, Result,
+};
+
+use super::{FromRow, ListFeatures};
+
+// Matches the formats chrono's own `Deserialize` implementations expect
+const DATE_FORMAT: &str = "%F";
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+pub fn from_row<'row, Value, Row>(row: &'row Row) -> Result<Value>
+where
+    Value: Deserialize<'row>,
+    Row: ListFeatures<Identifier = String>,
+{
+    Value::deserialize(RowDeserializer::new(row))
+}
+
+// Reads any type that serde can deserialize as a `FromRow`, by matching its fields to the row's
+// column names
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deserialized<Value>(pub Value);
+
+impl<Value: DeserializeOwned> FromRow for Deserialized<Value> {
+    fn from_row<Row: ListFeatures<Identifier = String>>(row: &Row) -> Result<Self> {
+        from_row(row).map(Self)
+    }
+}
+
+pub struct RowDeserializer<'row, Row> {
+    row: &'row Row,
+}
+
+impl<'row, Row> RowDeserializer<'row, Row> {
+    pub fn new(row: &'row Row) -> Self {
+        Self { row }
+    }
+}
+
+impl<'row, Row> de::Deserializer<'row> for RowDeserializer<'row, Row>
+where
+    Row: ListFeatures<Identifier = String>,
+{
+    type Error = Error;
+
+    fn deserialize_any<RowVisitor: Visitor<'row>>(
+        self,
+        visitor: RowVisitor,
+    ) -> Result<RowVisitor::Value> {
+        visitor.visit_map(RowMapAccess {
+            row: self.row,
+            identifiers: self.row.feature_identifiers()?.into_iter().enumerate(),
+            current_index: None,
+        })
+    }
+
+    fn deserialize_seq<RowVisitor: Visitor<'row>>(
+        self,
+        visitor: RowVisitor,
+    ) -> Result<RowVisitor::Value> {
+        // Sequences and tuples are read by position, which keeps every column of a join even when
+        // several of them share a name
+        let values = (0..self.row.feature_identifiers()?.len())
+            .map(|index| {
+                Ok(ValueDeserializer {
+                    value: self.row.take_feature_at(index)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        visitor.visit_seq(SeqDeserializer::new(values.into_iter()))
+    }
+
+    fn deserialize_tuple<RowVisitor: Visitor<'row>>(
+        self,
+        _len: usize,
+        visitor: RowVisitor,
+    ) -> Result<RowVisitor::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<RowVisitor: Visitor<'row>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: RowVisitor,
+    ) -> Result<RowVisitor::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        <RowVisitor: Visitor<'row>>
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option
+        unit unit_struct newtype_struct map struct enum identifier ignored_any
+    }
+}
+
+struct RowMapAccess<'row, Row> {
+    row: &'row Row,
+    identifiers: std::iter::Enumerate<std::vec::IntoIter<String>>,
+    current_index: Option<usize>,
+}
+
+impl<'row, Row> MapAccess<'row> for RowMapAccess<'row, Row>
+where
+    Row: ListFeatures<Identifier = String>,
+{
+    type Error = Error;
+
+    fn next_key_seed<Key: DeserializeSeed<'row>>(
+        &mut self,
+        seed: Key,
+    ) -> Result<Option<Key::Value>> {
+        let Some((index, identifier)) = self.identifiers.next() else {
+            return Ok(None);
+        };
+
+        let key = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(
+            identifier.as_str(),
+        ))?;
+        self.current_index = Some(index);
+
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<Value: DeserializeSeed<'row>>(
+        &mut self,
+        seed: Value,
+    ) -> Result<Value::Value> {
+        // Values are read by position rather than by name, so columns that share a name each keep
+        // their own value
+        let index = self
+            .current_index
+            .take()
+            .ok_or_else(|| Error::Deserialization {
+                message: "value requested before its column name".to_owned(),
+            })?;
+
+        seed.deserialize(ValueDeserializer {
+            value: self.row.take_feature_at(index)?,
+        })
+    }
+}
+
+struct ValueDeserializer<'row> {
+    value: Option<ValueUnion<'row>>,
+}
+
+impl<'row> IntoDeserializer<'row, Error> for ValueDeserializer<'row> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'row> de::Deserializer<'row> for ValueDeserializer<'row> {
+    type Error = Error;
+
+    fn deserialize_any<ValueVisitor: Visitor<'row>>(
+        self,
+        visitor: ValueVisitor,
+    ) -> Result<ValueVisitor::Value> {
+        let Some(value) = self.value else {
+            return visitor.visit_none();
+        };
+
+        match value {
+            ValueUnion::Null => visitor.visit_none(),
+            ValueUnion::List(values) => {
+                visitor.visit_seq(SeqDeserializer::new(values.iter().map(|value| {
+                    ValueDeserializer {
+                        value: Some(value.as_value_union()),
+                    }
+                })))
+            }
+            ValueUnion::Bool(value) => visitor.visit_bool(value),
+            ValueUnion::U8(value) => visitor.visit_u8(value),
+            ValueUnion::U16(value) => visitor.visit_u16(value),
+            ValueUnion::U32(value) => visitor.visit_u32(value),
+            ValueUnion::U64(value) => visitor.visit_u64(value),
+            ValueUnion::U128(value) => visitor.visit_u128(value),
+            ValueUnion::I8(value) => visitor.visit_i8(value),
+            ValueUnion::I16(value) => visitor.visit_i16(value),
+            ValueUnion::I32(value) => visitor.visit_i32(value),
+            ValueUnion::I64(value) => visitor.visit_i64(value),
+            ValueUnion::I128(value) => visitor.visit_i128(value),
+            ValueUnion::F32(value) => visitor.visit_f32(value),
+            ValueUnion::F64(value) => visitor.visit_f64(value),
+            ValueUnion::String(value) => visitor.visit_borrowed_str(value),
+            ValueUnion::Bytestring(value) => visitor.visit_borrowed_bytes(value),
+            ValueUnion::Date(value) => visitor.visit_string(value.format(DATE_FORMAT).to_string()),
+            ValueUnion::DateTime(value) => {
+                visitor.visit_string(value.format(DATETIME_FORMAT).to_string())
+            }
+            ValueUnion::DateTimeUtc(value) => visitor.visit_string(value.to_rfc3339()),
+            ValueUnion::DateTimeTz(value) => visitor.visit_string(value.to_rfc3339()),
+            ValueUnion::Time(value) => visitor.visit_string(value.format(TIME_FORMAT).to_string()),
+            #[cfg(feature = "decimal")]
+            ValueUnion::Decimal(value) => visitor.visit_string(value.to_string()),
+            #[cfg(feature = "uuid")]
+            ValueUnion::Uuid(value) => visitor.visit_string(value.to_string()),
+            #[cfg(feature = "json")]
+            ValueUnion::Json(value) => {
+                serde::Deserializer::deserialize_any((*value).clone(), visitor).map_err(|error| {
+                    Error::Deserialization {
+                        message: error.to_string(),
+                    }
+                })
+            }
+            ValueUnion::Duration(value) => visitor.visit_i64(duration_to_millis(value)),
+        }
+    }
+
+    fn deserialize_bool<ValueVisitor: Visitor<'row>>(
+        self,
+        visitor: ValueVisitor,
+    ) -> Result<ValueVisitor::Value> {
+        // SQLite has no boolean type, so booleans come back as whatever they were stored as and are
+        // converted the same way as when they are taken from a row directly
+        match &self.value {
+            Some(value) => {
+                visitor.visit_bool(bool::from_sql_value(value.convert_to(&bool::data_type())?)?)
+            }
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<ValueVisitor: Visitor<'row>>(
+        self,
+        visitor: ValueVisitor,
+    ) -> Result<ValueVisitor::Value> {
+        match self.value {
+            Some(ValueUnion::Null) | None => visitor.visit_none(),
+            Some(_) => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_seq<ValueVisitor: Visitor<'row>>(
+        self,
+        visitor: ValueVisitor,
+    ) -> Result<ValueVisitor::Value> {
+        // Lets fields like `Vec<u8>` be read from blobs
+        match self.value {
+            Some(ValueUnion::Bytestring(value)) => {
+                visitor.visit_seq(SeqDeserializer::new(value.iter().copied()))
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<ValueVisitor: Visitor<'row>>(
+        self,
+        _name: &'static str,
+        visitor: ValueVisitor,
+    ) -> Result<ValueVisitor::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<ValueVisitor: Visitor<'row>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: ValueVisitor,
+    ) -> Result<ValueVisitor::Value> {
+        match self.value {
+            Some(ValueUnion::String(value)) => {
+                visitor.visit_enum(IntoDeserializer::<Error>::into_deserializer(value.as_str()))
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        <ValueVisitor: Visitor<'row>>
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct tuple tuple_struct map struct identifier ignored_any
+    }
+}
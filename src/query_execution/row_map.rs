@@ -0,0 +1,233 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use crate::{domain::OwnedValueUnion, Result};
+
+use super::ListFeatures;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RowMap {
+    entries: Vec<(String, Option<OwnedValueUnion>)>,
+}
+
+impl RowMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_row<Row>(row: &Row) -> Result<Self>
+    where
+        Row: ListFeatures<Identifier = String>,
+    {
+        // Values are read by position so that columns sharing a name, as in a join, are all kept
+        let entries = row
+            .feature_identifiers()?
+            .into_iter()
+            .enumerate()
+            .map(|(index, identifier)| {
+                Ok((
+                    identifier,
+                    row.take_feature_at(index)?.map(OwnedValueUnion::from),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { entries })
+    }
+
+    // Like the columns of a row, entries may share a name, so this always adds a new entry at the
+    // end rather than replacing an existing one
+    pub fn insert<Name: Into<String>>(&mut self, name: Name, value: Option<OwnedValueUnion>) {
+        self.entries.push((name.into(), value));
+    }
+
+    // The outer option is whether the column exists, the inner one whether it is null. When
+    // several columns share the name, this is the first of them.
+    pub fn get(&self, name: &str) -> Option<Option<&OwnedValueUnion>> {
+        self.entries
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    pub fn get_at(&self, index: usize) -> Option<(&str, Option<&OwnedValueUnion>)> {
+        self.entries
+            .get(index)
+            .map(|(name, value)| (name.as_str(), value.as_ref()))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&OwnedValueUnion>)> {
+        self.entries
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_ref()))
+    }
+
+    // A JSON object cannot hold the same name twice, so the last column with a given name wins
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Object(
+            self.entries
+                .iter()
+                .map(|(name, value)| (name.clone(), json_from_value(value.as_ref())))
+                .collect(),
+        )
+    }
+}
+
+impl IntoIterator for RowMap {
+    type Item = (String, Option<OwnedValueUnion>);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<Name: Into<String>> FromIterator<(Name, Option<OwnedValueUnion>)> for RowMap {
+    fn from_iter<Entries: IntoIterator<Item = (Name, Option<OwnedValueUnion>)>>(
+        entries: Entries,
+    ) -> Self {
+        Self {
+            entries: entries
+                .into_iter()
+                .map(|(name, value)| (name.into(), value))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RowMap {
+    fn serialize<Serializer: serde::Serializer>(
+        &self,
+        serializer: Serializer,
+    ) -> std::result::Result<Serializer::Ok, Serializer::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+
+        for (name, value) in &self.entries {
+            map.serialize_entry(name, value)?;
+        }
+
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RowMap {
+    fn deserialize<Deserializer: serde::Deserializer<'de>>(
+        deserializer: Deserializer,
+    ) -> std::result::Result<Self, Deserializer::Error> {
+        struct RowMapVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for RowMapVisitor {
+            type Value = RowMap;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of column names to values")
+            }
+
+            fn visit_map<Map: serde::de::MapAccess<'de>>(
+                self,
+                mut map: Map,
+            ) -> std::result::Result<Self::Value, Map::Error> {
+                let mut row_map = RowMap::new();
+
+                // Names that appear more than once are all kept, as `insert` does
+                while let Some((name, value)) = map.next_entry::<String, _>()? {
+                    row_map.insert(name, value);
+                }
+
+                Ok(row_map)
+            }
+        }
+
+        deserializer.deserialize_map(RowMapVisitor)
+    }
+}
+
+#[cfg(feature = "json")]
+fn json_from_value(value: Option<&OwnedValueUnion>) -> serde_json::Value {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    use crate::domain::duration_to_millis;
+    use serde_json::Value;
+
+    let Some(value) = value else {
+        return Value::Null;
+    };
+
+    match value {
+        OwnedValueUnion::Null => Value::Null,
+        OwnedValueUnion::List(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| json_from_value(Some(value)))
+                .collect(),
+        ),
+        OwnedValueUnion::Bool(value) => Value::from(*value),
+        OwnedValueUnion::U8(value) => Value::from(*value),
+        OwnedValueUnion::U16(value) => Value::from(*value),
+        OwnedValueUnion::U32(value) => Value::from(*value),
+        OwnedValueUnion::U64(value) => Value::from(*value),
+        // JSON numbers beyond 64 bits lose precision in most parsers, so these become strings
+        OwnedValueUnion::U128(value) => u64::try_from(*value)
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::from(value.to_string())),
+        OwnedValueUnion::I8(value) => Value::from(*value),
+        OwnedValueUnion::I16(value) => Value::from(*value),
+        OwnedValueUnion::I32(value) => Value::from(*value),
+        OwnedValueUnion::I64(value) => Value::from(*value),
+        OwnedValueUnion::I128(value) => i64::try_from(*value)
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::from(value.to_string())),
+        // JSON has no representation for NaN or infinities, so these become null
+        OwnedValueUnion::F32(value) => Value::from(*value),
+        OwnedValueUnion::F64(value) => Value::from(*value),
+        OwnedValueUnion::String(value) => Value::from(value.as_str()),
+        OwnedValueUnion::Bytestring(value) => Value::from(STANDARD.encode(value)),
+        OwnedValueUnion::Date(value) => Value::from(value.format("%F").to_string()),
+        OwnedValueUnion::DateTime(value) => {
+            Value::from(value.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+        }
+        OwnedValueUnion::DateTimeUtc(value) => Value::from(value.to_rfc3339()),
+        OwnedValueUnion::DateTimeTz(value) => Value::from(value.to_rfc3339()),
+        OwnedValueUnion::Time(value) => Value::from(value.format("%H:%M:%S%.f").to_string()),
+        // Kept as a string so that no precision is lost
+        #[cfg(feature = "decimal")]
+        OwnedValueUnion::Decimal(value) => Value::from(value.to_string()),
+        #[cfg(feature = "uuid")]
+        OwnedValueUnion::Uuid(value) => Value::from(value.to_string()),
+        OwnedValueUnion::Json(value) => value.clone(),
+        OwnedValueUnion::Duration(value) => Value::from(duration_to_millis(value)),
+    }
+}
@@ -13,12 +13,32 @@
 // You should have received a copy of the GNU General Public License along with Bedrock ORM. If
 // not, see <https://www.gnu.org/licenses/>.
 
-use crate::domain::ValueUnion;
+use crate::domain::{DataType, OwnedValueUnion, SqlType, ValueUnion};
 
 use super::IdentifyFeature;
 
 pub trait TakeFeatures {
     type Identifier: IdentifyFeature;
 
-    fn take_feature(&self, identifier: &Self::Identifier) -> crate::Result<Option<ValueUnion>>;
+    fn take_feature(&self, identifier: &Self::Identifier) -> crate::Result<Option<ValueUnion<'_>>>;
+
+    fn take_feature_as(
+        &self,
+        identifier: &Self::Identifier,
+        data_type: &DataType,
+    ) -> crate::Result<Option<OwnedValueUnion>> {
+        self.take_feature(identifier)?
+            .map(|value| value.convert_to(data_type))
+            .transpose()
+    }
+
+    fn take_sql_value<Value: SqlType>(
+        &self,
+        identifier: &Self::Identifier,
+    ) -> crate::Result<Value> {
+        Value::from_sql_value(
+            self.take_feature_as(identifier, &Value::data_type())?
+                .unwrap_or(OwnedValueUnion::Null),
+        )
+    }
 }
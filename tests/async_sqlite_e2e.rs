@@ -0,0 +1,266 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use bedrock_orm::{
+    database_providers::{AsyncSqliteConnection, AsyncSqliteQuery},
+    domain::ValueUnion,
+    query_execution::{AsyncExecuteQuery, AsyncQueryResult, InjectFeatures, TakeFeatures},
+    Error,
+};
+use futures::{executor::block_on, StreamExt};
+
+async fn connect_memory() -> AsyncSqliteConnection {
+    AsyncSqliteConnection::connect_memory()
+        .await
+        .expect("unable to connect to sqlite database in memory")
+}
+
+async fn create_table_users(connection: &AsyncSqliteConnection) {
+    let mut query =
+        AsyncSqliteQuery::new_without_results("CREATE TABLE users (name TEXT, age INTEGER)");
+
+    let query_result = connection
+        .execute(&mut query)
+        .await
+        .expect("unable to execute query");
+
+    assert!(matches!(query_result, AsyncQueryResult::None));
+}
+
+async fn insert_user(connection: &AsyncSqliteConnection, name: &str, age: u32) -> usize {
+    let mut query =
+        AsyncSqliteQuery::new_with_change_count("INSERT INTO users VALUES (:name, :age)");
+
+    query
+        .inject_feature(&"name".to_owned(), &ValueUnion::String(&name.to_owned()))
+        .expect("unable to inject feature");
+    query
+        .inject_feature(&"age".to_owned(), &ValueUnion::U32(age))
+        .expect("unable to inject feature");
+
+    connection
+        .execute_with_change_count(&mut query)
+        .await
+        .expect("unable to execute query")
+}
+
+async fn select_users(connection: &AsyncSqliteConnection) -> Vec<(String, u32)> {
+    let mut query = AsyncSqliteQuery::new_with_iterator("SELECT name, age FROM users ORDER BY age");
+
+    let query_result = connection
+        .execute(&mut query)
+        .await
+        .expect("unable to execute query");
+
+    if let AsyncQueryResult::Stream { row_stream } = query_result {
+        let name = "name".to_owned();
+        let age = "age".to_owned();
+
+        row_stream
+            .map(|row| {
+                (
+                    TryInto::<&String>::try_into(
+                        row.take_feature(&name)
+                            .expect("unable to take feature")
+                            .expect("feature cannot be null"),
+                    )
+                    .expect("unable to convert feature")
+                    .clone(),
+                    TryInto::<i64>::try_into(
+                        row.take_feature(&age)
+                            .expect("unable to take feature")
+                            .expect("feature cannot be null"),
+                    )
+                    .expect("unable to convert feature") as u32,
+                )
+            })
+            .collect::<Vec<_>>()
+            .await
+    } else {
+        panic!("query result is not a stream");
+    }
+}
+
+#[test]
+fn test_async_connect_memory() {
+    block_on(connect_memory());
+}
+
+#[test]
+fn test_async_users_round_trip() {
+    block_on(async {
+        let connection = connect_memory().await;
+
+        create_table_users(&connection).await;
+
+        assert_eq!(insert_user(&connection, "Bob", 40).await, 1);
+        assert_eq!(insert_user(&connection, "Alice", 30).await, 1);
+
+        assert_eq!(
+            select_users(&connection).await,
+            vec![("Alice".to_owned(), 30), ("Bob".to_owned(), 40)]
+        );
+    });
+}
+
+#[test]
+fn test_async_stream_dropped_early() {
+    block_on(async {
+        let connection = connect_memory().await;
+
+        create_table_users(&connection).await;
+
+        for age in 0..200 {
+            insert_user(&connection, "Bob", age).await;
+        }
+
+        let mut query = AsyncSqliteQuery::new_with_iterator("SELECT name, age FROM users");
+        let mut row_stream = connection
+            .execute_with_iterator(&mut query)
+            .await
+            .expect("unable to execute query");
+
+        assert!(row_stream.next().await.is_some());
+        drop(row_stream);
+
+        // The worker must have moved on to serve new queries after the stream was dropped
+        assert_eq!(insert_user(&connection, "Alice", 30).await, 1);
+    });
+}
+
+#[test]
+fn test_async_query_while_stream_open() {
+    block_on(async {
+        let connection = connect_memory().await;
+
+        create_table_users(&connection).await;
+
+        for age in 0..200 {
+            insert_user(&connection, "Bob", age).await;
+        }
+
+        let mut query = AsyncSqliteQuery::new_with_iterator("SELECT name, age FROM users");
+        let mut row_stream = connection
+            .execute_with_iterator(&mut query)
+            .await
+            .expect("unable to execute query");
+
+        let mut row_count = 0;
+
+        // A lookup per row must not wait for the stream, which cannot finish until it is drained
+        while let Some(row) = row_stream.next().await {
+            let age = TryInto::<i64>::try_into(
+                row.take_feature(&"age".to_owned())
+                    .expect("unable to take feature")
+                    .expect("feature cannot be null"),
+            )
+            .expect("unable to convert feature");
+
+            let mut lookup = AsyncSqliteQuery::new_with_iterator(
+                "SELECT COUNT(*) AS count FROM users WHERE age < :age",
+            );
+            lookup
+                .inject_feature(&"age".to_owned(), &ValueUnion::I64(age))
+                .expect("unable to inject feature");
+
+            let counts = connection
+                .execute_with_iterator(&mut lookup)
+                .await
+                .expect("unable to execute query")
+                .collect::<Vec<_>>()
+                .await;
+            assert_eq!(counts.len(), 1);
+
+            row_count += 1;
+        }
+
+        assert_eq!(row_count, 200);
+    });
+}
+
+#[test]
+fn test_async_many_streams_open() {
+    block_on(async {
+        let connection = connect_memory().await;
+
+        create_table_users(&connection).await;
+
+        for age in 0..200 {
+            insert_user(&connection, "Bob", age).await;
+        }
+
+        let mut row_streams = Vec::new();
+
+        // Every stream is served by the connection's worker, so opening many of them at once does
+        // not need a thread each
+        for _ in 0..100 {
+            let mut query = AsyncSqliteQuery::new_with_iterator("SELECT name, age FROM users");
+
+            row_streams.push(
+                connection
+                    .execute_with_iterator(&mut query)
+                    .await
+                    .expect("unable to execute query"),
+            );
+        }
+
+        // Reading the streams in turns needs all of them to make progress side by side
+        for _ in 0..200 {
+            for row_stream in &mut row_streams {
+                assert!(row_stream.next().await.is_some());
+            }
+        }
+
+        for row_stream in &mut row_streams {
+            assert!(row_stream.next().await.is_none());
+        }
+
+        assert_eq!(insert_user(&connection, "Alice", 30).await, 1);
+    });
+}
+
+#[test]
+fn test_async_invalid_query() {
+    block_on(async {
+        let connection = connect_memory().await;
+
+        let mut query = AsyncSqliteQuery::new_with_iterator("SELECT * FROM missing_table");
+
+        assert!(matches!(
+            connection.execute_with_iterator(&mut query).await,
+            Err(Error::SchemaMismatch { .. })
+        ));
+    });
+}
+
+#[test]
+fn test_async_connection_across_threads() {
+    let connection = block_on(connect_memory());
+
+    block_on(create_table_users(&connection));
+
+    let handles = (0..4)
+        .map(|age| {
+            let connection = connection.clone();
+            std::thread::spawn(move || block_on(insert_user(&connection, "Carol", age)))
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        assert_eq!(handle.join().expect("inserting thread panicked"), 1);
+    }
+
+    assert_eq!(block_on(select_users(&connection)).len(), 4);
+}
@@ -0,0 +1,151 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::DataType,
+    query_building::{CreateTableQuery, Dialect},
+    query_execution::ExecuteQuery,
+    Error,
+};
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn data_type(dialect: Dialect, type_name: &str) -> DataType {
+    dialect
+        .data_type(type_name)
+        .expect("unable to map column type")
+}
+
+#[test]
+fn test_sqlite_affinity() {
+    for (type_name, expected) in [
+        ("INTEGER", DataType::I64),
+        ("unsigned big int", DataType::I64),
+        ("VARCHAR(20)", DataType::String),
+        ("NATIVE CHARACTER (70)", DataType::String),
+        ("CLOB", DataType::String),
+        ("BLOB", DataType::Bytestring),
+        ("", DataType::Bytestring),
+        ("DOUBLE PRECISION", DataType::F64),
+        ("FLOAT", DataType::F64),
+        ("BOOLEAN", DataType::Bool),
+        ("DATE", DataType::Date),
+        ("DATETIME", DataType::DateTime),
+        ("TIME", DataType::Time),
+        // Any name containing INT has integer affinity, even when it spells something else
+        ("POINT", DataType::I64),
+        ("TINYINT(1)", DataType::I64),
+        // Any name that matches no other rule has numeric affinity
+        ("MONEY", DataType::F64),
+    ] {
+        assert_eq!(
+            data_type(Dialect::Sqlite, type_name),
+            expected,
+            "{type_name}"
+        );
+    }
+}
+
+#[test]
+fn test_other_dialects() {
+    assert_eq!(data_type(Dialect::Postgres, "int4"), DataType::I32);
+    assert_eq!(
+        data_type(Dialect::Postgres, "timestamp with time zone"),
+        DataType::DateTimeUtc
+    );
+    assert_eq!(
+        data_type(Dialect::Postgres, "character varying(255)"),
+        DataType::String
+    );
+    assert_eq!(data_type(Dialect::MySql, "int(10) unsigned"), DataType::U32);
+    assert_eq!(data_type(Dialect::MySql, "LONGBLOB"), DataType::Bytestring);
+    assert_eq!(data_type(Dialect::MySql, "tinyint(1)"), DataType::Bool);
+    assert_eq!(data_type(Dialect::MySql, "TINYINT(4)"), DataType::I8);
+    assert!(matches!(
+        Dialect::Postgres.data_type("tsvector"),
+        Err(Error::UnknownColumnType { .. })
+    ));
+}
+
+#[test]
+fn test_type_names_round_trip() {
+    for dialect in [Dialect::Sqlite, Dialect::Postgres, Dialect::MySql] {
+        for data_type in [
+            DataType::Bool,
+            DataType::I64,
+            DataType::F64,
+            DataType::String,
+            DataType::Bytestring,
+            DataType::Date,
+            DataType::DateTime,
+            DataType::Time,
+        ] {
+            let type_name = dialect
+                .type_name(&data_type)
+                .expect("unable to render type name");
+
+            assert_eq!(
+                dialect
+                    .data_type(type_name)
+                    .expect("unable to map column type"),
+                data_type,
+                "{dialect:?} {type_name}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_table_columns() {
+    let connection = connect_memory();
+    execute_ok(
+        &connection,
+        CreateTableQuery::new("users")
+            .column("id", DataType::I64)
+            .column("name", DataType::String)
+            .column("born", DataType::Date.nullable())
+            .column("score", DataType::F64.nullable())
+            .render(Dialect::Sqlite)
+            .expect("unable to render query")
+            .text(),
+    );
+
+    assert_eq!(
+        connection
+            .table_columns("users")
+            .expect("unable to read columns"),
+        vec![
+            ("id".to_owned(), DataType::I64),
+            ("name".to_owned(), DataType::String),
+            ("born".to_owned(), DataType::Date.nullable()),
+            ("score".to_owned(), DataType::F64.nullable()),
+        ]
+    );
+    assert!(matches!(
+        connection.table_columns("missing"),
+        Err(Error::SchemaMismatch { .. })
+    ));
+}
@@ -0,0 +1,161 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::{DataType, OwnedValueUnion, ValueUnion},
+    query_execution::{ExecuteQuery, InjectFeatures, TakeFeatures},
+    Error,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn select_bound(
+    connection: &SqliteConnection,
+    value: &ValueUnion,
+    data_type: &DataType,
+) -> (String, OwnedValueUnion) {
+    let mut query = SqliteQuery::new_with_iterator(connection, "SELECT :value AS value")
+        .expect("unable to create query");
+    query
+        .inject_feature(&"value".to_owned(), value)
+        .expect("unable to inject feature");
+
+    let row = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("expected a row");
+
+    let text = row
+        .take_feature(&"value".to_owned())
+        .expect("unable to take feature")
+        .expect("feature cannot be null");
+    let text = <&String>::try_from(text)
+        .expect("value is not stored as text")
+        .clone();
+
+    let converted = row
+        .take_feature_as(&"value".to_owned(), data_type)
+        .expect("unable to convert feature")
+        .expect("feature cannot be null");
+
+    (text, converted)
+}
+
+fn tokyo_datetime() -> DateTime<FixedOffset> {
+    FixedOffset::east_opt(9 * 3600)
+        .expect("invalid offset")
+        .with_ymd_and_hms(2023, 7, 4, 21, 30, 45)
+        .single()
+        .expect("invalid datetime")
+}
+
+#[test]
+fn test_datetime_tz_round_trip() {
+    let connection = connect_memory();
+    let value = tokyo_datetime();
+
+    let (text, converted) = select_bound(
+        &connection,
+        &ValueUnion::DateTimeTz(&value),
+        &DataType::DateTimeTz,
+    );
+
+    assert_eq!(text, "2023-07-04T21:30:45+09:00");
+    assert_eq!(converted, OwnedValueUnion::DateTimeTz(value));
+}
+
+#[test]
+fn test_datetime_tz_normalized_to_utc() {
+    let connection = connect_memory();
+    let value = tokyo_datetime();
+
+    let (_, converted) = select_bound(
+        &connection,
+        &ValueUnion::DateTimeTz(&value),
+        &DataType::DateTimeUtc,
+    );
+
+    assert_eq!(
+        converted,
+        OwnedValueUnion::DateTimeUtc(
+            Utc.with_ymd_and_hms(2023, 7, 4, 12, 30, 45)
+                .single()
+                .expect("invalid datetime")
+        )
+    );
+}
+
+#[test]
+fn test_datetime_utc_round_trip() {
+    let connection = connect_memory();
+    let value = Utc
+        .with_ymd_and_hms(2023, 1, 2, 3, 4, 5)
+        .single()
+        .expect("invalid datetime");
+
+    let (text, converted) = select_bound(
+        &connection,
+        &ValueUnion::DateTimeUtc(&value),
+        &DataType::DateTimeUtc,
+    );
+
+    assert_eq!(text, "2023-01-02T03:04:05+00:00");
+    assert_eq!(converted, OwnedValueUnion::DateTimeUtc(value));
+}
+
+#[test]
+fn test_naive_datetime_round_trip() {
+    let connection = connect_memory();
+    let value = NaiveDate::from_ymd_opt(2023, 1, 2)
+        .and_then(|date| date.and_hms_milli_opt(3, 4, 5, 600))
+        .expect("invalid datetime");
+
+    let (text, converted) = select_bound(
+        &connection,
+        &ValueUnion::DateTime(&value),
+        &DataType::DateTime,
+    );
+
+    assert_eq!(text, "2023-01-02T03:04:05.600");
+    assert_eq!(converted, OwnedValueUnion::DateTime(value));
+}
+
+#[test]
+fn test_convert_invalid_datetime() {
+    let text = "not a datetime".to_owned();
+
+    assert!(matches!(
+        ValueUnion::String(&text).convert_to(&DataType::DateTimeTz),
+        Err(Error::ValueCannotBeAccessedAsRequestedType {
+            value_type: DataType::String,
+            requested_type: DataType::DateTimeTz,
+        })
+    ));
+    assert!(matches!(
+        ValueUnion::I64(300).convert_to(&DataType::U8),
+        Err(Error::ValueCannotBeAccessedAsRequestedType { .. })
+    ));
+    assert_eq!(
+        ValueUnion::I64(1)
+            .convert_to(&DataType::Bool)
+            .expect("unable to convert"),
+        OwnedValueUnion::Bool(true)
+    );
+}
@@ -0,0 +1,158 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(feature = "decimal")]
+
+use std::str::FromStr;
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::{DataType, OwnedValueUnion, ValueUnion},
+    query_execution::{ExecuteQuery, InjectFeatures, PageCursor, TakeFeatures},
+};
+use rust_decimal::Decimal;
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn insert_amount(connection: &SqliteConnection, amount: &Decimal) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, "INSERT INTO payments VALUES (:amount)")
+            .expect("unable to create query");
+
+    query
+        .inject_feature(&"amount".to_owned(), &ValueUnion::from(amount))
+        .expect("unable to inject feature");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn select_amounts(connection: &SqliteConnection) -> Vec<Decimal> {
+    let mut query = SqliteQuery::new_with_iterator(connection, "SELECT amount FROM payments")
+        .expect("unable to create query");
+
+    let amounts = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| {
+            match row
+                .take_feature_as(&"amount".to_owned(), &DataType::Decimal)
+                .expect("unable to convert feature")
+            {
+                Some(OwnedValueUnion::Decimal(amount)) => amount,
+                value => panic!("unexpected value {value:?}"),
+            }
+        })
+        .collect();
+
+    amounts
+}
+
+fn decimal(text: &str) -> Decimal {
+    Decimal::from_str(text).expect("invalid decimal")
+}
+
+#[test]
+fn test_decimal_arithmetic_round_trip() {
+    let connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE payments (amount TEXT)");
+
+    for amount in ["0.10", "0.20", "19.99"] {
+        insert_amount(&connection, &decimal(amount));
+    }
+
+    let amounts = select_amounts(&connection);
+
+    assert_eq!(
+        amounts,
+        vec![decimal("0.10"), decimal("0.20"), decimal("19.99")]
+    );
+    assert_eq!(amounts.iter().sum::<Decimal>().to_string(), "20.29");
+}
+
+#[test]
+fn test_decimal_stored_as_canonical_text() {
+    let connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE payments (amount TEXT)");
+
+    let amount = decimal("12345678901234567890.123456789");
+    insert_amount(&connection, &amount);
+
+    let mut query = SqliteQuery::new_with_iterator(
+        &connection,
+        "SELECT amount, typeof(amount) AS amount_type FROM payments",
+    )
+    .expect("unable to create query");
+    let row = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("expected a row");
+
+    let stored = row
+        .take_feature(&"amount".to_owned())
+        .expect("unable to take feature")
+        .expect("feature cannot be null");
+    let amount_type = row
+        .take_feature(&"amount_type".to_owned())
+        .expect("unable to take feature")
+        .expect("feature cannot be null");
+
+    assert_eq!(
+        <&String>::try_from(stored).expect("amount is not text"),
+        "12345678901234567890.123456789"
+    );
+    assert_eq!(
+        <&String>::try_from(amount_type).expect("type is not text"),
+        "text"
+    );
+    assert_eq!(select_amounts(&connection), vec![amount]);
+}
+
+#[test]
+fn test_decimal_from_numeric_affinity() {
+    let connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE payments (amount NUMERIC)");
+
+    insert_amount(&connection, &decimal("42"));
+    insert_amount(&connection, &decimal("2.5"));
+
+    assert_eq!(
+        select_amounts(&connection),
+        vec![decimal("42"), decimal("2.5")]
+    );
+}
+
+#[test]
+fn test_decimal_page_cursor() {
+    let cursor = PageCursor::new(vec![OwnedValueUnion::Decimal(decimal("-0.000123"))]);
+
+    assert_eq!(
+        PageCursor::from_token(&cursor.to_token()).expect("unable to decode cursor"),
+        cursor
+    );
+}
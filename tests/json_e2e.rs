@@ -0,0 +1,250 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+#![cfg(feature = "json")]
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::{DataType, OwnedValueUnion, ValueUnion},
+    query_building::{Dialect, JsonExpression},
+    query_execution::{ExecuteQuery, InjectFeatures, PageCursor, TakeFeatures},
+    Error,
+};
+use serde_json::{json, Value};
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn insert_profile(connection: &SqliteConnection, name: &str, profile: &Value) {
+    let name = name.to_owned();
+    let mut query =
+        SqliteQuery::new_without_results(connection, "INSERT INTO users VALUES (:name, :profile)")
+            .expect("unable to create query");
+
+    query
+        .inject_feature(&"name".to_owned(), &ValueUnion::from(&name))
+        .expect("unable to inject feature");
+    query
+        .inject_feature(&"profile".to_owned(), &ValueUnion::from(profile))
+        .expect("unable to inject feature");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn select_names(connection: &SqliteConnection, query_text: &str, city: &str) -> Vec<String> {
+    let city = city.to_owned();
+    let mut query =
+        SqliteQuery::new_with_iterator(connection, query_text).expect("unable to create query");
+
+    query
+        .inject_feature(&"city".to_owned(), &ValueUnion::from(&city))
+        .expect("unable to inject feature");
+
+    let names = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| {
+            match row
+                .take_feature_as(&"name".to_owned(), &DataType::String)
+                .expect("unable to convert feature")
+            {
+                Some(OwnedValueUnion::String(name)) => name,
+                value => panic!("unexpected value {value:?}"),
+            }
+        })
+        .collect();
+
+    names
+}
+
+fn populate(connection: &SqliteConnection) {
+    execute_ok(connection, "CREATE TABLE users (name TEXT, profile TEXT)");
+
+    insert_profile(
+        connection,
+        "alice",
+        &json!({"address": {"city": "Oslo"}, "tags": ["admin", "ops"]}),
+    );
+    insert_profile(
+        connection,
+        "bob",
+        &json!({"address": {"city": "Lima"}, "tags": ["ops"]}),
+    );
+}
+
+#[test]
+fn test_json_round_trip() {
+    let connection = connect_memory();
+    populate(&connection);
+
+    let mut query = SqliteQuery::new_with_iterator(
+        &connection,
+        "SELECT profile FROM users WHERE name = 'alice'",
+    )
+    .expect("unable to create query");
+    let row = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("expected a row");
+
+    assert_eq!(
+        row.take_feature_as(&"profile".to_owned(), &DataType::Json)
+            .expect("unable to convert feature"),
+        Some(OwnedValueUnion::Json(
+            json!({"address": {"city": "Oslo"}, "tags": ["admin", "ops"]})
+        ))
+    );
+}
+
+#[test]
+fn test_json_malformed_content() {
+    let connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE users (name TEXT, profile TEXT)");
+    execute_ok(
+        &connection,
+        "INSERT INTO users VALUES ('carol', '{\"address\":')",
+    );
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT profile FROM users")
+        .expect("unable to create query");
+    let row = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("expected a row");
+
+    assert!(matches!(
+        row.take_feature_as(&"profile".to_owned(), &DataType::Json),
+        Err(Error::InvalidJson { .. })
+    ));
+}
+
+#[test]
+fn test_json_filter_on_nested_field() {
+    let connection = connect_memory();
+    populate(&connection);
+
+    for expression in [
+        JsonExpression::extract("profile", "$.address.city"),
+        JsonExpression::arrow_text("profile", "$.address.city"),
+    ] {
+        let query_text = format!(
+            "SELECT name FROM users WHERE {} = :city",
+            expression
+                .render(Dialect::Sqlite)
+                .expect("unable to render expression")
+        );
+
+        assert_eq!(select_names(&connection, &query_text, "Lima"), ["bob"]);
+    }
+}
+
+#[test]
+fn test_json_qualified_column_in_join() {
+    let connection = connect_memory();
+    populate(&connection);
+
+    let query_text = format!(
+        "SELECT u.name FROM users u JOIN users other ON other.name = u.name WHERE {} = :city",
+        JsonExpression::arrow_text("u.profile", "$.address.city")
+            .render(Dialect::Sqlite)
+            .expect("unable to render expression")
+    );
+
+    assert_eq!(select_names(&connection, &query_text, "Lima"), ["bob"]);
+}
+
+#[test]
+fn test_json_each() {
+    let connection = connect_memory();
+    populate(&connection);
+
+    let query_text = format!(
+        "SELECT name, value AS tag FROM users, {} ORDER BY name, tag",
+        JsonExpression::each_at("profile", "$.tags")
+            .render(Dialect::Sqlite)
+            .expect("unable to render expression")
+    );
+    let mut query =
+        SqliteQuery::new_with_iterator(&connection, &query_text).expect("unable to create query");
+
+    let tags = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| {
+            match row
+                .take_feature_as(&"tag".to_owned(), &DataType::String)
+                .expect("unable to convert feature")
+            {
+                Some(OwnedValueUnion::String(tag)) => tag,
+                value => panic!("unexpected value {value:?}"),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(tags, ["admin", "ops", "ops"]);
+}
+
+#[test]
+fn test_json_render_dialects() {
+    assert_eq!(
+        JsonExpression::arrow("profile", "$.name")
+            .render(Dialect::Sqlite)
+            .expect("unable to render expression"),
+        "\"profile\" -> '$.name'"
+    );
+    assert_eq!(
+        JsonExpression::extract("profile", "$.o'brien")
+            .render(Dialect::MySql)
+            .expect("unable to render expression"),
+        "JSON_EXTRACT(`profile`, '$.o''brien')"
+    );
+    assert_eq!(
+        JsonExpression::arrow_text("u.profile", "$.name")
+            .render(Dialect::MySql)
+            .expect("unable to render expression"),
+        "`u`.`profile` ->> '$.name'"
+    );
+    assert!(matches!(
+        JsonExpression::each("profile").render(Dialect::MySql),
+        Err(Error::InvalidQuery { .. })
+    ));
+    assert!(matches!(
+        JsonExpression::extract("profile", "name").render(Dialect::Sqlite),
+        Err(Error::InvalidQuery { .. })
+    ));
+}
+
+#[test]
+fn test_json_page_cursor() {
+    let cursor = PageCursor::new(vec![OwnedValueUnion::Json(json!({"a": [1, 2.5, null]}))]);
+
+    assert_eq!(
+        PageCursor::from_token(&cursor.to_token()).expect("unable to decode cursor"),
+        cursor
+    );
+}
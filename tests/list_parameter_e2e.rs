@@ -0,0 +1,167 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::{OwnedValueUnion, ValueUnion},
+    query_building::expand_list_parameter,
+    query_execution::{ExecuteQuery, InjectFeatures, PageCursor, TakeFeatures},
+    Error,
+};
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn populate(connection: &SqliteConnection) {
+    execute_ok(connection, "CREATE TABLE users (id INTEGER, name TEXT)");
+    execute_ok(
+        connection,
+        "INSERT INTO users VALUES (1, 'alice'), (2, 'bob'), (3, 'carol'), (4, 'dan \"the man\"')",
+    );
+}
+
+fn select_ids(mut query: SqliteQuery, connection: &SqliteConnection) -> Vec<i64> {
+    let ids = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| {
+            row.take_sql_value::<i64>(&"id".to_owned())
+                .expect("unable to take feature")
+        })
+        .collect();
+
+    ids
+}
+
+#[test]
+fn test_expand_list_parameter() {
+    assert_eq!(
+        expand_list_parameter(
+            "SELECT * FROM users WHERE id IN (:ids) AND id != :ids_other",
+            "ids",
+            3
+        )
+        .expect("unable to expand parameter"),
+        "SELECT * FROM users WHERE id IN (:ids_0, :ids_1, :ids_2) AND id != :ids_other"
+    );
+    assert!(matches!(
+        expand_list_parameter("SELECT * FROM users WHERE id = :id", "ids", 3),
+        Err(Error::InvalidQuery { .. })
+    ));
+}
+
+#[test]
+fn test_expanded_in_list() {
+    let connection = connect_memory();
+    populate(&connection);
+
+    for ids in [vec![1i64, 3], vec![2], vec![]] {
+        let query_text = expand_list_parameter(
+            "SELECT id FROM users WHERE id IN (:ids) ORDER BY id",
+            "ids",
+            ids.len(),
+        )
+        .expect("unable to expand parameter");
+        let mut query = SqliteQuery::new_with_iterator(&connection, &query_text)
+            .expect("unable to create query");
+
+        query
+            .inject_sql_values(&"ids".to_owned(), &ids)
+            .expect("unable to inject feature");
+
+        assert_eq!(select_ids(query, &connection), ids);
+    }
+}
+
+#[test]
+fn test_json_each_in_list() {
+    let connection = connect_memory();
+    populate(&connection);
+
+    let mut query = SqliteQuery::new_with_iterator(
+        &connection,
+        "SELECT id FROM users WHERE name IN (SELECT value FROM json_each(:names)) ORDER BY id",
+    )
+    .expect("unable to create query");
+
+    query
+        .inject_sql_values(
+            &"names".to_owned(),
+            &["carol".to_owned(), "dan \"the man\"".to_owned()],
+        )
+        .expect("unable to inject feature");
+
+    assert_eq!(select_ids(query, &connection), [3, 4]);
+}
+
+#[test]
+fn test_list_rejects_blobs() {
+    let connection = connect_memory();
+    populate(&connection);
+
+    let values = [OwnedValueUnion::Bytestring(vec![1, 2])];
+    let mut query = SqliteQuery::new_with_iterator(
+        &connection,
+        "SELECT id FROM users WHERE id IN (SELECT value FROM json_each(:ids))",
+    )
+    .expect("unable to create query");
+
+    assert!(matches!(
+        query.inject_feature(&"ids".to_owned(), &ValueUnion::List(&values)),
+        Err(Error::InvalidQuery { .. })
+    ));
+}
+
+#[test]
+fn test_list_page_cursor() {
+    let cursor = PageCursor::new(vec![OwnedValueUnion::List(vec![
+        OwnedValueUnion::I64(1),
+        OwnedValueUnion::String("two".to_owned()),
+        OwnedValueUnion::Null,
+    ])]);
+
+    assert_eq!(
+        PageCursor::from_token(&cursor.to_token()).expect("unable to decode cursor"),
+        cursor
+    );
+}
+
+#[test]
+fn test_deeply_nested_page_cursor() {
+    // Version byte followed by lists that each hold one more list
+    let mut bytes = vec![1];
+
+    for _ in 0..100_000 {
+        bytes.push(25);
+        bytes.extend(1u32.to_be_bytes());
+    }
+
+    bytes.push(24);
+
+    assert!(matches!(
+        PageCursor::from_token(&URL_SAFE_NO_PAD.encode(bytes)),
+        Err(Error::InvalidPageCursor { .. })
+    ));
+}
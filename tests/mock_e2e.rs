@@ -0,0 +1,170 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+use bedrock_orm::{
+    database_providers::{MockConnection, MockExpectation, MockQuery},
+    domain::OwnedValueUnion,
+    query_execution::{ExecuteQuery, InjectFeatures, PrepareQuery, QueryResultType, TakeFeatures},
+    Error,
+};
+
+fn user_names<Connection>(connection: &Connection, min_age: i64) -> bedrock_orm::Result<Vec<String>>
+where
+    Connection: PrepareQuery,
+    <Connection::Query as InjectFeatures>::Identifier: From<&'static str>,
+    <Connection::Row as TakeFeatures>::Identifier: From<&'static str>,
+{
+    let mut query = connection.prepare_query(
+        "SELECT name FROM users WHERE age >= :min_age ORDER BY name",
+        QueryResultType::Iterator,
+    )?;
+    query.inject_sql_value(&"min_age".into(), &min_age)?;
+
+    let names = connection
+        .execute_with_iterator(&mut query)?
+        .map(|row| row.take_sql_value(&"name".into()))
+        .collect();
+    names
+}
+
+#[test]
+fn test_scripted_rows() {
+    let connection = MockConnection::new();
+    connection.expect(
+        MockExpectation::new("SELECT name FROM users WHERE age >= :min_age ORDER BY name")
+            .parameter("min_age", 18i64)
+            .returning_rows(
+                &["name"],
+                vec![
+                    vec![OwnedValueUnion::String("alice".to_owned())],
+                    vec![OwnedValueUnion::String("bob".to_owned())],
+                ],
+            ),
+    );
+
+    assert_eq!(
+        user_names(&connection, 18).expect("unable to query users"),
+        vec!["alice".to_owned(), "bob".to_owned()]
+    );
+    connection.verify().expect("expectations were not met");
+}
+
+#[test]
+fn test_change_count_and_errors() {
+    let connection = MockConnection::new();
+    connection.expect(
+        MockExpectation::new("DELETE FROM users WHERE id = :id")
+            .parameter("id", 1i32)
+            .returning_change_count(1),
+    );
+    connection.expect(
+        MockExpectation::new("DELETE FROM users WHERE id = :id")
+            .parameter("id", 2i32)
+            .failing_with(Error::DatabaseBusy {
+                message: "database is locked".to_owned(),
+            }),
+    );
+
+    for (id, expected) in [(1i64, Some(1)), (2, None)] {
+        let mut query =
+            MockQuery::new_with_change_count(&connection, "DELETE FROM users WHERE id = :id");
+        query
+            .inject_sql_value(&"id".to_owned(), &id)
+            .expect("unable to inject parameter");
+
+        let result = connection.execute_with_change_count(&mut query);
+
+        match expected {
+            Some(count) => assert_eq!(result.expect("unable to execute query"), count),
+            None => assert!(matches!(result, Err(Error::DatabaseBusy { .. }))),
+        }
+    }
+
+    connection.verify().expect("expectations were not met");
+}
+
+#[test]
+fn test_prepare_errors() {
+    let connection = MockConnection::new();
+    connection.expect(
+        MockExpectation::new("SELECT * FROM missing").failing_to_prepare_with(
+            Error::SchemaMismatch {
+                object: Some("missing".to_owned()),
+                message: "no such table: missing".to_owned(),
+            },
+        ),
+    );
+
+    assert!(matches!(
+        connection.prepare_query("SELECT * FROM missing", QueryResultType::Iterator),
+        Err(Error::SchemaMismatch { .. })
+    ));
+    connection.verify().expect("expectations were not met");
+}
+
+#[test]
+fn test_mismatched_query() {
+    let connection = MockConnection::new();
+    connection.expect(MockExpectation::new("SELECT name FROM users"));
+
+    let mut query = MockQuery::new_without_results(&connection, "SELECT id FROM users");
+
+    assert!(matches!(
+        connection.execute_without_results(&mut query),
+        Err(Error::UnexpectedQuery { .. })
+    ));
+    assert!(matches!(
+        connection.verify(),
+        Err(Error::UnmetExpectations { .. })
+    ));
+}
+
+#[test]
+fn test_mismatched_parameters() {
+    let connection = MockConnection::new();
+    connection.expect(
+        MockExpectation::new("SELECT name FROM users WHERE age >= :min_age ORDER BY name")
+            .parameter("min_age", 21i64)
+            .returning_rows(&["name"], vec![]),
+    );
+
+    assert!(matches!(
+        user_names(&connection, 18),
+        Err(Error::UnexpectedQuery { .. })
+    ));
+}
+
+#[test]
+fn test_unexpected_and_unmet_queries() {
+    let connection = MockConnection::new();
+    let mut query = MockQuery::new_without_results(&connection, "DELETE FROM users");
+
+    assert!(matches!(
+        connection.execute_without_results(&mut query),
+        Err(Error::UnexpectedQuery { .. })
+    ));
+
+    connection.expect(MockExpectation::new("DELETE FROM users"));
+    connection.expect(MockExpectation::new("DELETE FROM posts"));
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+
+    match connection.verify() {
+        Err(Error::UnmetExpectations { queries }) => {
+            assert_eq!(queries, vec!["DELETE FROM posts".to_owned()])
+        }
+        result => panic!("expected unmet expectations, got {result:?}"),
+    }
+}
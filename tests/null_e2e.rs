@@ -0,0 +1,149 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::{DataType, OwnedValueUnion, ValueUnion},
+    query_execution::{ExecuteQuery, InjectFeatures, PageCursor, TakeFeatures},
+    Error,
+};
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn select_ages(connection: &SqliteConnection) -> Vec<Option<OwnedValueUnion>> {
+    let mut query = SqliteQuery::new_with_iterator(connection, "SELECT age FROM users")
+        .expect("unable to create query");
+
+    let ages = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| {
+            row.take_feature_as(&"age".to_owned(), &DataType::U32.nullable())
+                .expect("unable to convert feature")
+        })
+        .collect();
+
+    ages
+}
+
+#[test]
+fn test_bind_null() {
+    let connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE users (age INTEGER)");
+
+    let mut query =
+        SqliteQuery::new_without_results(&connection, "INSERT INTO users VALUES (:age)")
+            .expect("unable to create query");
+
+    for age in [Some(30u32), None] {
+        query.reset().expect("unable to reset query");
+        query
+            .inject_feature(&"age".to_owned(), &ValueUnion::from(age))
+            .expect("unable to inject feature");
+        connection
+            .execute_without_results(&mut query)
+            .expect("unable to execute query");
+    }
+
+    assert_eq!(
+        select_ages(&connection),
+        vec![Some(OwnedValueUnion::U32(30)), None]
+    );
+}
+
+#[test]
+fn test_not_null_constraint() {
+    let connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE users (age INTEGER NOT NULL)");
+
+    let mut query =
+        SqliteQuery::new_without_results(&connection, "INSERT INTO users VALUES (:age)")
+            .expect("unable to create query");
+    query
+        .inject_feature(&"age".to_owned(), &ValueUnion::Null)
+        .expect("unable to inject feature");
+
+    assert!(matches!(
+        connection.execute_without_results(&mut query),
+        Err(Error::NotNullConstraintViolation { .. })
+    ));
+}
+
+#[test]
+fn test_option_conversions() {
+    assert!(matches!(ValueUnion::from(None::<i64>), ValueUnion::Null));
+    assert!(matches!(ValueUnion::from(Some(5i64)), ValueUnion::I64(5)));
+
+    assert_eq!(
+        Option::<i64>::try_from(ValueUnion::Null).expect("unable to convert value"),
+        None
+    );
+    assert_eq!(
+        Option::<i64>::try_from(ValueUnion::I64(5)).expect("unable to convert value"),
+        Some(5)
+    );
+    assert!(matches!(
+        Option::<i64>::try_from(ValueUnion::Bool(true)),
+        Err(Error::ValueCannotBeAccessedAsRequestedType { .. })
+    ));
+}
+
+#[test]
+fn test_nullable_data_type() {
+    let nullable = DataType::I64.nullable();
+
+    assert_eq!(nullable, DataType::Nullable(Box::new(DataType::I64)));
+    assert_eq!(nullable.clone().nullable(), nullable);
+    assert!(nullable.is_nullable());
+    assert!(!DataType::I64.is_nullable());
+    assert_eq!(nullable.non_nullable(), &DataType::I64);
+
+    assert_eq!(
+        ValueUnion::Null
+            .convert_to(&nullable)
+            .expect("unable to convert value"),
+        OwnedValueUnion::Null
+    );
+    assert_eq!(
+        ValueUnion::I32(7)
+            .convert_to(&nullable)
+            .expect("unable to convert value"),
+        OwnedValueUnion::I64(7)
+    );
+    assert!(matches!(
+        ValueUnion::Null.convert_to(&DataType::I64),
+        Err(Error::ValueCannotBeAccessedAsRequestedType { .. })
+    ));
+}
+
+#[test]
+fn test_null_page_cursor() {
+    let cursor = PageCursor::new(vec![OwnedValueUnion::Null, OwnedValueUnion::I64(3)]);
+
+    assert_eq!(
+        PageCursor::from_token(&cursor.to_token()).expect("unable to decode cursor"),
+        cursor
+    );
+}
@@ -0,0 +1,348 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use bedrock_orm::{
+    database_providers::{MockConnection, MockExpectation, SqliteConnection, SqliteQuery},
+    domain::{OwnedValueUnion, ValueUnion},
+    query_building::{Dialect, SortOrder},
+    query_execution::{
+        BulkInsert, ExecuteQuery, InjectFeatures, Keyset, PageCursor, Paginate, TakeFeatures,
+    },
+    Error,
+};
+use chrono::{FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+
+fn connect_with_items(count: i64) -> SqliteConnection {
+    let connection = SqliteConnection::connect_memory().expect("unable to connect to database");
+
+    let mut query = SqliteQuery::new_without_results(
+        &connection,
+        "CREATE TABLE items (id INTEGER PRIMARY KEY, category TEXT NOT NULL, name TEXT NOT NULL)",
+    )
+    .expect("unable to prepare query");
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to create table");
+
+    let even = "even".to_owned();
+    let odd = "odd".to_owned();
+    let name = "item".to_owned();
+
+    let rows = (1..=count)
+        .map(|id| {
+            [
+                ValueUnion::I64(id),
+                ValueUnion::String(if id % 2 == 0 { &even } else { &odd }),
+                ValueUnion::String(&name),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    connection
+        .bulk_insert("items", &["id", "category", "name"], rows)
+        .expect("unable to insert items");
+
+    connection
+}
+
+fn ids(rows: &[<SqliteConnection as ExecuteQuery>::Row]) -> Vec<i64> {
+    rows.iter()
+        .map(|row| {
+            row.take_feature(&"id".to_owned())
+                .expect("unable to take id")
+                .expect("id is null")
+                .try_into()
+                .expect("id is not an integer")
+        })
+        .collect()
+}
+
+fn no_bindings(_: &mut SqliteQuery) -> bedrock_orm::Result<()> {
+    Ok(())
+}
+
+#[test]
+fn test_offset_page() {
+    let connection = connect_with_items(25);
+
+    let page = connection
+        .fetch_offset_page(
+            "SELECT * FROM items",
+            no_bindings,
+            &[("id", SortOrder::Ascending)],
+            10,
+            10,
+        )
+        .expect("unable to fetch page");
+
+    assert_eq!(ids(&page.rows), (11..=20).collect::<Vec<_>>());
+    assert_eq!(page.total_count, 25);
+    assert_eq!(page.page_count(), 3);
+    assert!(page.has_next_page());
+
+    let last_page = connection
+        .fetch_offset_page(
+            "SELECT * FROM items;",
+            no_bindings,
+            &[("id", SortOrder::Ascending)],
+            20,
+            10,
+        )
+        .expect("unable to fetch page");
+
+    assert_eq!(ids(&last_page.rows), (21..=25).collect::<Vec<_>>());
+    assert!(!last_page.has_next_page());
+}
+
+#[test]
+fn test_offset_page_with_bindings() {
+    let connection = connect_with_items(25);
+    let category = "even".to_owned();
+
+    let page = connection
+        .fetch_offset_page(
+            "SELECT * FROM items WHERE category = :category",
+            |query| query.inject_feature(&"category".to_owned(), &ValueUnion::String(&category)),
+            &[("id", SortOrder::Ascending)],
+            0,
+            5,
+        )
+        .expect("unable to fetch page");
+
+    assert_eq!(ids(&page.rows), vec![2, 4, 6, 8, 10]);
+    assert_eq!(page.total_count, 12);
+}
+
+#[test]
+fn test_keyset_pages_ascending() {
+    let connection = connect_with_items(7);
+    let keyset = Keyset::ascending(["id"]);
+
+    let mut seen = Vec::new();
+    let mut cursor = None;
+
+    loop {
+        let page = connection
+            .fetch_keyset_page(
+                "SELECT * FROM items",
+                no_bindings,
+                &keyset,
+                cursor.as_ref(),
+                3,
+            )
+            .expect("unable to fetch page");
+
+        seen.extend(ids(&page.rows));
+
+        match page.next_cursor {
+            // Round trip through the opaque token like a client would
+            Some(next_cursor) => {
+                cursor = Some(
+                    PageCursor::from_token(&next_cursor.to_token())
+                        .expect("unable to decode cursor"),
+                )
+            }
+            None => break,
+        }
+    }
+
+    assert_eq!(seen, (1..=7).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_keyset_pages_descending_compound_key() {
+    let connection = connect_with_items(6);
+    let keyset = Keyset::descending(["category", "id"]);
+
+    let first_page = connection
+        .fetch_keyset_page("SELECT * FROM items", no_bindings, &keyset, None, 4)
+        .expect("unable to fetch page");
+
+    assert_eq!(ids(&first_page.rows), vec![5, 3, 1, 6]);
+
+    let second_page = connection
+        .fetch_keyset_page(
+            "SELECT * FROM items",
+            no_bindings,
+            &keyset,
+            first_page.next_cursor.as_ref(),
+            4,
+        )
+        .expect("unable to fetch page");
+
+    assert_eq!(ids(&second_page.rows), vec![4, 2]);
+    assert!(!second_page.has_next_page());
+}
+
+#[test]
+fn test_page_cursor_round_trip() {
+    let cursor = PageCursor::new(vec![
+        OwnedValueUnion::Bool(true),
+        OwnedValueUnion::U64(u64::MAX),
+        OwnedValueUnion::I16(-3),
+        OwnedValueUnion::F64(1.5),
+        OwnedValueUnion::String("héllo".to_owned()),
+        OwnedValueUnion::Bytestring(vec![0, 1, 2]),
+        OwnedValueUnion::Date(NaiveDate::from_ymd_opt(2023, 7, 4).expect("invalid date")),
+        OwnedValueUnion::DateTime(
+            NaiveDate::from_ymd_opt(2023, 7, 4)
+                .and_then(|date| date.and_hms_nano_opt(12, 30, 45, 123))
+                .expect("invalid datetime"),
+        ),
+        OwnedValueUnion::DateTimeUtc(
+            Utc.timestamp_opt(1_688_473_845, 5)
+                .single()
+                .expect("invalid datetime"),
+        ),
+        OwnedValueUnion::DateTimeTz(
+            FixedOffset::west_opt(5 * 3600)
+                .expect("invalid offset")
+                .timestamp_opt(1_688_473_845, 0)
+                .single()
+                .expect("invalid datetime"),
+        ),
+        OwnedValueUnion::Time(NaiveTime::from_hms_micro_opt(8, 30, 0, 5).expect("invalid time")),
+        OwnedValueUnion::Duration(Duration::new(90, 7)),
+    ]);
+
+    let token = cursor.to_token();
+
+    assert!(token
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(
+        PageCursor::from_token(&token).expect("unable to decode cursor"),
+        cursor
+    );
+}
+
+#[test]
+fn test_invalid_page_cursor() {
+    assert!(matches!(
+        PageCursor::from_token("not a token"),
+        Err(Error::InvalidPageCursor { .. })
+    ));
+
+    let truncated = PageCursor::new(vec![OwnedValueUnion::I64(1)]).to_token();
+
+    assert!(matches!(
+        PageCursor::from_token(&truncated[..truncated.len() - 2]),
+        Err(Error::InvalidPageCursor { .. })
+    ));
+
+    let connection = connect_with_items(3);
+    let cursor = PageCursor::new(vec![OwnedValueUnion::I64(1), OwnedValueUnion::I64(2)]);
+
+    assert!(matches!(
+        connection.fetch_keyset_page(
+            "SELECT * FROM items",
+            no_bindings,
+            &Keyset::ascending(["id"]),
+            Some(&cursor),
+            2,
+        ),
+        Err(Error::InvalidPageCursor { .. })
+    ));
+}
+
+#[test]
+fn test_invalid_pagination() {
+    let connection = connect_with_items(3);
+
+    assert!(matches!(
+        connection.fetch_offset_page(
+            "SELECT * FROM items",
+            no_bindings,
+            &[("id", SortOrder::Ascending)],
+            0,
+            0
+        ),
+        Err(Error::InvalidPagination { .. })
+    ));
+    assert!(matches!(
+        connection.fetch_offset_page("SELECT * FROM items", no_bindings, &[], 0, 2),
+        Err(Error::InvalidPagination { .. })
+    ));
+    assert!(matches!(
+        connection.fetch_keyset_page(
+            "SELECT * FROM items",
+            no_bindings,
+            &Keyset::ascending(Vec::<String>::new()),
+            None,
+            2,
+        ),
+        Err(Error::InvalidPagination { .. })
+    ));
+}
+
+#[test]
+fn test_offset_page_descending() {
+    let connection = connect_with_items(25);
+
+    let page = connection
+        .fetch_offset_page(
+            "SELECT * FROM items",
+            no_bindings,
+            &[
+                ("category", SortOrder::Ascending),
+                ("id", SortOrder::Descending),
+            ],
+            0,
+            3,
+        )
+        .expect("unable to fetch page");
+
+    assert_eq!(ids(&page.rows), vec![24, 22, 20]);
+}
+
+#[test]
+fn test_keyset_placeholders_follow_dialect() {
+    for (dialect, inner_query_text, expected_query_text) in [
+        (
+            Dialect::Postgres,
+            "SELECT * FROM items WHERE category = $1",
+            "SELECT * FROM (SELECT * FROM items WHERE category = $1) AS paginated \
+             WHERE (\"id\") > ($2) ORDER BY \"id\" ASC LIMIT 3",
+        ),
+        (
+            Dialect::MySql,
+            "SELECT * FROM items WHERE category = ?",
+            "SELECT * FROM (SELECT * FROM items WHERE category = ?) AS paginated \
+             WHERE (`id`) > (?) ORDER BY `id` ASC LIMIT 3",
+        ),
+    ] {
+        let connection = MockConnection::with_dialect(dialect);
+        connection.expect(
+            MockExpectation::new(expected_query_text)
+                .parameter("bedrock_orm_page_key_0", 4i64)
+                .returning_rows(&["id"], vec![]),
+        );
+
+        let page = connection
+            .fetch_keyset_page(
+                inner_query_text,
+                |_| Ok(()),
+                &Keyset::ascending(["id"]),
+                Some(&PageCursor::new(vec![OwnedValueUnion::I64(4)])),
+                2,
+            )
+            .expect("unable to fetch page");
+
+        assert!(page.rows.is_empty());
+        connection.verify().expect("expectations were not met");
+    }
+}
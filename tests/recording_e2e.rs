@@ -0,0 +1,406 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+#![cfg(feature = "recording")]
+
+use std::{fs, path::Path, time::Duration};
+
+use bedrock_orm::{
+    database_providers::{MockConnection, MockExpectation, RecordingConnection, SqliteConnection},
+    domain::OwnedValueUnion,
+    query_building::Dialect,
+    query_execution::{
+        ExecuteQuery, GetDialect, InjectFeatures, PrepareQuery, QueryResultType, RowStream,
+        TakeFeatures,
+    },
+    Error,
+};
+use chrono::DateTime;
+use tempfile::TempDir;
+
+fn run<Connection>(connection: &Connection, query_text: &str) -> bedrock_orm::Result<()>
+where
+    Connection: PrepareQuery,
+{
+    let mut query = connection.prepare_query(query_text, QueryResultType::None)?;
+    connection.execute_without_results(&mut query)
+}
+
+fn report<Connection>(
+    connection: &Connection,
+    min_total: i64,
+) -> bedrock_orm::Result<Vec<(String, i64)>>
+where
+    Connection: PrepareQuery,
+    <Connection::Query as InjectFeatures>::Identifier: From<&'static str>,
+    <Connection::Row as TakeFeatures>::Identifier: From<&'static str>,
+{
+    let mut query = connection.prepare_query(
+        "SELECT customer, SUM(amount) AS total FROM orders GROUP BY customer \
+         HAVING total >= :min_total ORDER BY customer",
+        QueryResultType::Iterator,
+    )?;
+    query.inject_sql_value(&"min_total".into(), &min_total)?;
+
+    let rows = connection
+        .execute_with_iterator(&mut query)?
+        .map(|row| {
+            Ok((
+                row.take_sql_value(&"customer".into())?,
+                row.take_sql_value(&"total".into())?,
+            ))
+        })
+        .collect();
+    rows
+}
+
+fn select_parameters<Connection>(
+    connection: &Connection,
+    query_text: &str,
+    parameters: &[(&'static str, OwnedValueUnion)],
+) -> bedrock_orm::Result<usize>
+where
+    Connection: PrepareQuery,
+    <Connection::Query as InjectFeatures>::Identifier: From<&'static str>,
+{
+    let mut query = connection.prepare_query(query_text, QueryResultType::Iterator)?;
+
+    for (name, value) in parameters {
+        query.inject_feature(&(*name).into(), &value.as_value_union())?;
+    }
+
+    let rows = connection.execute_with_iterator(&mut query)?.count();
+    Ok(rows)
+}
+
+fn record_session(path: &Path) -> Vec<(String, i64)> {
+    let connection = RecordingConnection::new(
+        SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory"),
+        path,
+    )
+    .expect("unable to create recording");
+
+    run(
+        &connection,
+        "CREATE TABLE orders (customer TEXT NOT NULL, amount INTEGER NOT NULL)",
+    )
+    .expect("unable to create table");
+    run(
+        &connection,
+        "INSERT INTO orders VALUES ('alice', 10), ('alice', 15), ('bob', 5), ('carol', 30)",
+    )
+    .expect("unable to insert rows");
+
+    report(&connection, 20).expect("unable to run report")
+}
+
+#[test]
+fn test_replay_matches_recording() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+
+    let recorded = record_session(&path);
+    assert_eq!(
+        recorded,
+        vec![("alice".to_owned(), 25), ("carol".to_owned(), 30)]
+    );
+
+    let connection = MockConnection::replay(&path).expect("unable to replay recording");
+    run(
+        &connection,
+        "CREATE TABLE orders (customer TEXT NOT NULL, amount INTEGER NOT NULL)",
+    )
+    .expect("unable to replay create table");
+    run(
+        &connection,
+        "INSERT INTO orders VALUES ('alice', 10), ('alice', 15), ('bob', 5), ('carol', 30)",
+    )
+    .expect("unable to replay insert");
+
+    assert_eq!(
+        report(&connection, 20).expect("unable to replay report"),
+        recorded
+    );
+    connection
+        .verify()
+        .expect("recording was not fully replayed");
+}
+
+#[test]
+fn test_recording_is_readable() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+    record_session(&path);
+
+    let contents = fs::read_to_string(&path).expect("unable to read recording");
+    let lines = contents.lines().collect::<Vec<_>>();
+
+    assert_eq!(
+        lines[1],
+        r#"{"query":"CREATE TABLE orders (customer TEXT NOT NULL, amount INTEGER NOT NULL)","parameters":{}}"#
+    );
+    assert!(lines[3].ends_with(r#""parameters":{"min_total":{"I64":20}}}"#));
+    assert_eq!(
+        &lines[4..],
+        [
+            r#"{"columns":["customer","total"]}"#,
+            r#"{"row":[{"String":"alice"},{"I64":25}]}"#,
+            r#"{"row":[{"String":"carol"},{"I64":30}]}"#,
+        ]
+    );
+}
+
+#[test]
+fn test_parameter_types_round_trip() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+    let query_text = "SELECT :id AS id, :ratio AS ratio, :avatar AS avatar, :seen AS seen, \
+                      :wait AS wait, :note AS note, :nickname AS nickname";
+    let parameters = [
+        ("id", OwnedValueUnion::I32(-7)),
+        ("ratio", OwnedValueUnion::F64(f64::INFINITY)),
+        ("avatar", OwnedValueUnion::Bytestring(vec![0, 1, 254, 255])),
+        (
+            "seen",
+            OwnedValueUnion::DateTimeTz(
+                DateTime::parse_from_rfc3339("2023-05-01T13:00:00.123456789+02:00")
+                    .expect("invalid datetime"),
+            ),
+        ),
+        (
+            "wait",
+            OwnedValueUnion::Duration(Duration::new(3, 500_000_000)),
+        ),
+        (
+            "note",
+            OwnedValueUnion::String("it's \"quoted\" ✓".to_owned()),
+        ),
+        ("nickname", OwnedValueUnion::Null),
+    ];
+
+    let connection = RecordingConnection::new(
+        SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory"),
+        &path,
+    )
+    .expect("unable to create recording");
+    assert_eq!(
+        select_parameters(&connection, query_text, &parameters).expect("unable to execute query"),
+        1
+    );
+
+    let connection = MockConnection::replay(&path).expect("unable to replay recording");
+    assert_eq!(
+        select_parameters(&connection, query_text, &parameters).expect("unable to replay query"),
+        1
+    );
+    connection
+        .verify()
+        .expect("recording was not fully replayed");
+}
+
+#[test]
+fn test_replay_flags_mismatches() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+    record_session(&path);
+
+    let connection = MockConnection::replay(&path).expect("unable to replay recording");
+    assert!(matches!(
+        run(&connection, "CREATE TABLE orders (customer TEXT)"),
+        Err(Error::UnexpectedQuery { .. })
+    ));
+
+    let connection = MockConnection::replay(&path).expect("unable to replay recording");
+    run(
+        &connection,
+        "CREATE TABLE orders (customer TEXT NOT NULL, amount INTEGER NOT NULL)",
+    )
+    .expect("unable to replay create table");
+    run(
+        &connection,
+        "INSERT INTO orders VALUES ('alice', 10), ('alice', 15), ('bob', 5), ('carol', 30)",
+    )
+    .expect("unable to replay insert");
+    assert!(matches!(
+        report(&connection, 10),
+        Err(Error::UnexpectedQuery { .. })
+    ));
+}
+
+#[test]
+fn test_errors_are_recorded() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+
+    let connection = RecordingConnection::new(
+        SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory"),
+        &path,
+    )
+    .expect("unable to create recording");
+    let mut query = connection
+        .prepare_query("SELECT :value AS value", QueryResultType::Iterator)
+        .expect("unable to prepare query");
+    query
+        .inject_feature(&"value".to_owned(), &OwnedValueUnion::Null.as_value_union())
+        .expect("unable to inject parameter");
+    let values = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| row.take_sql_value::<Option<i64>>(&"value".to_owned()))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("unable to read rows");
+    assert_eq!(values, vec![None]);
+    assert!(run(&connection, "DROP TABLE missing").is_err());
+
+    let connection = MockConnection::replay(&path).expect("unable to replay recording");
+    let mut query = connection
+        .prepare_query("SELECT :value AS value", QueryResultType::Iterator)
+        .expect("unable to prepare query");
+    query
+        .inject_feature(&"value".to_owned(), &OwnedValueUnion::Null.as_value_union())
+        .expect("unable to inject parameter");
+    let values = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to replay query")
+        .map(|row| row.take_sql_value::<Option<i64>>(&"value".to_owned()))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("unable to read rows");
+    assert_eq!(values, vec![None]);
+    assert!(matches!(
+        run(&connection, "DROP TABLE missing"),
+        Err(Error::SchemaMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_recorded_errors_keep_their_kind() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+
+    let connection = RecordingConnection::new(
+        SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory"),
+        &path,
+    )
+    .expect("unable to create recording");
+    run(&connection, "CREATE TABLE users (name TEXT UNIQUE)").expect("unable to create table");
+    run(&connection, "INSERT INTO users VALUES ('alice')").expect("unable to insert row");
+    let recorded = run(&connection, "INSERT INTO users VALUES ('alice')")
+        .expect_err("duplicate row was inserted");
+
+    let connection = MockConnection::replay(&path).expect("unable to replay recording");
+    run(&connection, "CREATE TABLE users (name TEXT UNIQUE)").expect("unable to replay create");
+    run(&connection, "INSERT INTO users VALUES ('alice')").expect("unable to replay insert");
+    let replayed = run(&connection, "INSERT INTO users VALUES ('alice')")
+        .expect_err("duplicate row was replayed as inserted");
+
+    assert!(matches!(
+        &replayed,
+        Error::UniqueConstraintViolation { columns, .. } if columns == &["users.name"]
+    ));
+    assert_eq!(replayed.to_string(), recorded.to_string());
+}
+
+#[test]
+fn test_empty_results_keep_their_columns() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+
+    let connection = RecordingConnection::new(
+        SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory"),
+        &path,
+    )
+    .expect("unable to create recording");
+    let mut query = connection
+        .prepare_query(
+            "SELECT 1 AS id, 'a' AS name WHERE 0",
+            QueryResultType::Iterator,
+        )
+        .expect("unable to prepare query");
+    let row_iterator = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query");
+    assert_eq!(row_iterator.column_names(), ["id", "name"]);
+    assert_eq!(row_iterator.count(), 0);
+
+    let connection = MockConnection::replay(&path).expect("unable to replay recording");
+    let mut query = connection
+        .prepare_query(
+            "SELECT 1 AS id, 'a' AS name WHERE 0",
+            QueryResultType::Iterator,
+        )
+        .expect("unable to prepare query");
+    let row_iterator = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to replay query");
+    assert_eq!(row_iterator.column_names(), ["id", "name"]);
+    assert_eq!(row_iterator.count(), 0);
+}
+
+#[test]
+fn test_invalid_recording() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+    fs::write(&path, "not a recording\n").expect("unable to write file");
+
+    assert!(matches!(
+        MockConnection::replay(&path),
+        Err(Error::InvalidRecording { .. })
+    ));
+    assert!(matches!(
+        MockConnection::replay(directory.path().join("missing.recording")),
+        Err(Error::IoError { .. })
+    ));
+}
+
+#[test]
+fn test_replay_keeps_dialect() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+
+    let mock = MockConnection::with_dialect(Dialect::Postgres);
+    mock.expect(MockExpectation::new("DELETE FROM orders"));
+    let connection = RecordingConnection::new(mock, &path).expect("unable to create recording");
+    run(&connection, "DELETE FROM orders").expect("unable to delete rows");
+
+    let connection = MockConnection::replay(&path).expect("unable to replay recording");
+    assert_eq!(connection.dialect(), Dialect::Postgres);
+    run(&connection, "DELETE FROM orders").expect("unable to replay delete");
+}
+
+#[test]
+fn test_prepare_errors_are_replayed_when_preparing() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("session.recording");
+    let query_text = "SELECT * FROM missing WHERE id = :id";
+    let parameters = [("id", OwnedValueUnion::I64(1))];
+
+    let connection = RecordingConnection::new(
+        SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory"),
+        &path,
+    )
+    .expect("unable to create recording");
+    assert!(matches!(
+        select_parameters(&connection, query_text, &parameters),
+        Err(Error::SchemaMismatch { .. })
+    ));
+
+    let connection = MockConnection::replay(&path).expect("unable to replay recording");
+    assert!(matches!(
+        select_parameters(&connection, query_text, &parameters),
+        Err(Error::SchemaMismatch { .. })
+    ));
+    connection
+        .verify()
+        .expect("recording was not fully replayed");
+}
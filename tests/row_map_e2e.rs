@@ -0,0 +1,177 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::OwnedValueUnion,
+    query_execution::{ExecuteQuery, RowMap},
+};
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn select_row_maps(connection: &SqliteConnection, query_text: &str) -> Vec<RowMap> {
+    let mut query =
+        SqliteQuery::new_with_iterator(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| RowMap::from_row(&row).expect("unable to convert row"))
+        .collect()
+}
+
+#[test]
+fn test_row_map_from_row() {
+    let connection = connect_memory();
+
+    let row_maps = select_row_maps(
+        &connection,
+        "SELECT 'Alice' AS name, NULL AS nickname, 42 AS age, upper('x')",
+    );
+
+    assert_eq!(row_maps.len(), 1);
+
+    let row_map = &row_maps[0];
+
+    assert_eq!(
+        row_map.names().collect::<Vec<_>>(),
+        vec!["name", "nickname", "age", "upper('x')"]
+    );
+    assert_eq!(
+        row_map.get("name"),
+        Some(Some(&OwnedValueUnion::String("Alice".to_owned())))
+    );
+    assert_eq!(row_map.get("nickname"), Some(None));
+    assert_eq!(row_map.get("age"), Some(Some(&OwnedValueUnion::I64(42))));
+    assert_eq!(
+        row_map.get("upper('x')"),
+        Some(Some(&OwnedValueUnion::String("X".to_owned())))
+    );
+    assert_eq!(row_map.get("missing"), None);
+}
+
+#[test]
+fn test_row_map_keeps_duplicate_columns() {
+    let connection = connect_memory();
+
+    for query_text in [
+        "CREATE TABLE authors (id INTEGER, name TEXT)",
+        "CREATE TABLE books (id INTEGER, author_id INTEGER, name TEXT)",
+        "INSERT INTO authors VALUES (1, 'Ursula')",
+        "INSERT INTO books VALUES (7, 1, 'Earthsea')",
+    ] {
+        let mut query = SqliteQuery::new_without_results(&connection, query_text)
+            .expect("unable to create query");
+
+        connection
+            .execute_without_results(&mut query)
+            .expect("unable to execute query");
+    }
+
+    let row_maps = select_row_maps(
+        &connection,
+        "SELECT a.id, b.id, a.name, b.name FROM authors a JOIN books b ON b.author_id = a.id",
+    );
+
+    assert_eq!(row_maps.len(), 1);
+
+    let row_map = &row_maps[0];
+
+    assert_eq!(row_map.len(), 4);
+    assert_eq!(
+        row_map.names().collect::<Vec<_>>(),
+        vec!["id", "id", "name", "name"]
+    );
+    assert_eq!(
+        row_map.get_at(1),
+        Some(("id", Some(&OwnedValueUnion::I64(7))))
+    );
+    assert_eq!(
+        row_map.get_at(3),
+        Some((
+            "name",
+            Some(&OwnedValueUnion::String("Earthsea".to_owned()))
+        ))
+    );
+    assert_eq!(row_map.get("id"), Some(Some(&OwnedValueUnion::I64(1))));
+    assert_eq!(row_map.get_at(4), None);
+}
+
+#[test]
+fn test_row_map_insert() {
+    let mut row_map: RowMap = [
+        ("id", Some(OwnedValueUnion::I64(1))),
+        ("name", None),
+        ("id", Some(OwnedValueUnion::I64(2))),
+    ]
+    .into_iter()
+    .collect();
+
+    // Duplicate names are kept, the same as they are for the columns of a row
+    assert_eq!(row_map.len(), 3);
+    assert_eq!(row_map.get("id"), Some(Some(&OwnedValueUnion::I64(1))));
+    assert_eq!(
+        row_map.get_at(2),
+        Some(("id", Some(&OwnedValueUnion::I64(2))))
+    );
+
+    row_map.insert("active", Some(OwnedValueUnion::Bool(true)));
+    row_map.insert("active", Some(OwnedValueUnion::Bool(false)));
+
+    assert!(row_map.contains("active"));
+    assert_eq!(
+        row_map.get("active"),
+        Some(Some(&OwnedValueUnion::Bool(true)))
+    );
+    assert_eq!(
+        row_map
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>(),
+        vec!["id", "name", "id", "active", "active"]
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_row_map_to_json() {
+    let connection = connect_memory();
+
+    let row_maps = select_row_maps(
+        &connection,
+        "SELECT 2 AS id, 'Bob' AS name, NULL AS nickname, 1.5 AS score, x'000102' AS avatar",
+    );
+
+    assert_eq!(
+        row_maps[0].to_json().to_string(),
+        r#"{"id":2,"name":"Bob","nickname":null,"score":1.5,"avatar":"AAEC"}"#
+    );
+
+    let mut row_map = RowMap::new();
+    row_map.insert(
+        "born",
+        Some(OwnedValueUnion::Date(
+            chrono::NaiveDate::from_ymd_opt(1990, 1, 31).expect("invalid date"),
+        )),
+    );
+    row_map.insert("ratio", Some(OwnedValueUnion::F64(f64::NAN)));
+
+    assert_eq!(
+        row_map.to_json(),
+        serde_json::json!({ "born": "1990-01-31", "ratio": null })
+    );
+}
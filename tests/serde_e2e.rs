@@ -0,0 +1,583 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(feature = "serde")]
+
+use std::{collections::BTreeMap, fmt};
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    query_execution::{
+        from_row, Deserialized, ExecuteQuery, FromRow, InjectSerialized, ListFeatures,
+    },
+    Error,
+};
+use chrono::NaiveDate;
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Serialize,
+};
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct User {
+    name: String,
+    age: u32,
+    active: bool,
+    role: Role,
+    born: NaiveDate,
+    nickname: Option<String>,
+    avatar: Option<Vec<u8>>,
+}
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn create_table_users(connection: &SqliteConnection) {
+    execute_ok(
+        connection,
+        "CREATE TABLE users (name TEXT, age INTEGER, active TEXT, role TEXT, born TEXT, \
+         nickname TEXT, avatar BLOB)",
+    );
+}
+
+fn insert_user<Parameters: Serialize + ?Sized>(
+    connection: &SqliteConnection,
+    parameters: &Parameters,
+) -> bedrock_orm::Result<()> {
+    let mut query = SqliteQuery::new_without_results(
+        connection,
+        "INSERT INTO users VALUES (:name, :age, :active, :role, :born, :nickname, :avatar)",
+    )?;
+
+    query.inject_serialized(parameters)?;
+
+    connection.execute_without_results(&mut query)
+}
+
+fn select_users<Value: for<'row> Deserialize<'row>>(
+    connection: &SqliteConnection,
+) -> bedrock_orm::Result<Vec<Value>> {
+    let mut query = SqliteQuery::new_with_iterator(connection, "SELECT * FROM users")?;
+
+    let users = connection
+        .execute_with_iterator(&mut query)?
+        .map(|row| from_row(&row))
+        .collect();
+
+    users
+}
+
+#[test]
+fn test_serialized_parameters_round_trip() {
+    let connection = connect_memory();
+    create_table_users(&connection);
+
+    let user = User {
+        name: "Alice".to_owned(),
+        age: 30,
+        active: true,
+        role: Role::Admin,
+        born: NaiveDate::from_ymd_opt(1993, 4, 5).expect("invalid date"),
+        nickname: None,
+        avatar: Some(vec![1, 2, 3]),
+    };
+
+    insert_user(&connection, &user).expect("unable to insert user");
+
+    assert_eq!(
+        select_users::<User>(&connection).expect("unable to select users"),
+        vec![user]
+    );
+}
+
+#[test]
+fn test_from_row_through_deserialized() {
+    let connection = connect_memory();
+    create_table_users(&connection);
+
+    let user = User {
+        name: "Dave".to_owned(),
+        age: 52,
+        active: false,
+        role: Role::Member,
+        born: NaiveDate::from_ymd_opt(1971, 8, 9).expect("invalid date"),
+        nickname: Some("D".to_owned()),
+        avatar: None,
+    };
+
+    insert_user(&connection, &user).expect("unable to insert user");
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT * FROM users")
+        .expect("unable to create query");
+    let users = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| Deserialized::<User>::from_row(&row).map(|Deserialized(user)| user))
+        .collect::<bedrock_orm::Result<Vec<_>>>()
+        .expect("unable to read users");
+
+    assert_eq!(users, [user]);
+}
+
+#[test]
+fn test_map_parameters() {
+    let connection = connect_memory();
+    create_table_users(&connection);
+
+    let parameters = BTreeMap::from([
+        ("name", "Bob"),
+        ("age", "41"),
+        ("active", "false"),
+        ("role", "Member"),
+        ("born", "1982-11-30"),
+    ]);
+
+    insert_user(&connection, &parameters).expect("unable to insert user");
+
+    let users = select_users::<User>(&connection).expect("unable to select users");
+
+    assert_eq!(users[0].name, "Bob");
+    assert_eq!(users[0].age, 41);
+    assert!(!users[0].active);
+    assert_eq!(users[0].role, Role::Member);
+    assert_eq!(users[0].nickname, None);
+}
+
+#[test]
+fn test_deserialize_borrowed_and_partial() {
+    #[derive(Deserialize)]
+    struct Summary<'row> {
+        name: &'row str,
+        missing: Option<i64>,
+    }
+
+    let connection = connect_memory();
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT 'Carol' AS name, 1 AS id")
+        .expect("unable to create query");
+    let row = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("expected a row");
+
+    let summary: Summary = from_row(&row).expect("unable to deserialize row");
+
+    assert_eq!(summary.name, "Carol");
+    assert_eq!(summary.missing, None);
+}
+
+#[test]
+fn test_deserialize_type_mismatch() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Count {
+        count: u8,
+    }
+
+    let connection = connect_memory();
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT 1000 AS count")
+        .expect("unable to create query");
+    let row = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("expected a row");
+
+    assert!(matches!(
+        from_row::<Count, _>(&row),
+        Err(Error::Deserialization { .. })
+    ));
+}
+
+fn select_row(
+    connection: &SqliteConnection,
+    query_text: &str,
+) -> <SqliteConnection as ExecuteQuery>::Row {
+    let mut query =
+        SqliteQuery::new_with_iterator(connection, query_text).expect("unable to create query");
+
+    let row = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("expected a row");
+
+    row
+}
+
+#[test]
+fn test_deserialize_duplicate_columns() {
+    // Keeps every entry of the map the row is read as, in order
+    struct Columns(Vec<(String, i64)>);
+
+    impl<'row> Deserialize<'row> for Columns {
+        fn deserialize<Deserializer: serde::Deserializer<'row>>(
+            deserializer: Deserializer,
+        ) -> Result<Self, Deserializer::Error> {
+            struct ColumnsVisitor;
+
+            impl<'row> Visitor<'row> for ColumnsVisitor {
+                type Value = Columns;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a map of columns")
+                }
+
+                fn visit_map<Access: MapAccess<'row>>(
+                    self,
+                    mut access: Access,
+                ) -> Result<Columns, Access::Error> {
+                    let mut columns = Vec::new();
+
+                    while let Some(column) = access.next_entry()? {
+                        columns.push(column);
+                    }
+
+                    Ok(Columns(columns))
+                }
+            }
+
+            deserializer.deserialize_map(ColumnsVisitor)
+        }
+    }
+
+    let connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE users (id INTEGER, name TEXT)");
+    execute_ok(&connection, "CREATE TABLE pets (id INTEGER, owner INTEGER)");
+    execute_ok(&connection, "INSERT INTO users VALUES (1, 'Alice')");
+    execute_ok(&connection, "INSERT INTO pets VALUES (2, 1)");
+
+    let row = select_row(
+        &connection,
+        "SELECT users.id, pets.id FROM users JOIN pets ON pets.owner = users.id",
+    );
+
+    assert_eq!(
+        from_row::<(i64, i64), _>(&row).expect("unable to deserialize row"),
+        (1, 2)
+    );
+    assert_eq!(
+        from_row::<Vec<i64>, _>(&row).expect("unable to deserialize row"),
+        vec![1, 2]
+    );
+    assert_eq!(
+        from_row::<Columns, _>(&row)
+            .expect("unable to deserialize row")
+            .0,
+        vec![("id".to_owned(), 1), ("id".to_owned(), 2)]
+    );
+}
+
+#[test]
+fn test_deserialize_booleans() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Flags {
+        text_true: bool,
+        text_false: bool,
+        integer_true: bool,
+        integer_false: bool,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Flag {
+        flag: bool,
+    }
+
+    let connection = connect_memory();
+
+    let row = select_row(
+        &connection,
+        "SELECT 'true' AS text_true, 'false' AS text_false, 1 AS integer_true, \
+         0 AS integer_false",
+    );
+
+    assert_eq!(
+        from_row::<Flags, _>(&row).expect("unable to deserialize row"),
+        Flags {
+            text_true: true,
+            text_false: false,
+            integer_true: true,
+            integer_false: false,
+        }
+    );
+
+    for query_text in ["SELECT 'yes' AS flag", "SELECT 2 AS flag"] {
+        assert!(from_row::<Flag, _>(&select_row(&connection, query_text)).is_err());
+    }
+}
+
+#[test]
+fn test_unsupported_parameters() {
+    #[derive(Serialize)]
+    struct Nested {
+        user: BTreeMap<String, String>,
+    }
+
+    let connection = connect_memory();
+    create_table_users(&connection);
+
+    assert!(matches!(
+        insert_user(
+            &connection,
+            &Nested {
+                user: BTreeMap::new()
+            }
+        ),
+        Err(Error::Serialization { .. })
+    ));
+    assert!(matches!(
+        insert_user(&connection, &42),
+        Err(Error::Serialization { .. })
+    ));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_row_map_round_trip() {
+    use bedrock_orm::{domain::OwnedValueUnion, query_execution::RowMap};
+
+    let row_map: RowMap = [
+        ("id", Some(OwnedValueUnion::I64(7))),
+        ("name", Some(OwnedValueUnion::String("Dave".to_owned()))),
+        ("nickname", None),
+    ]
+    .into_iter()
+    .collect();
+
+    let json = serde_json::to_string(&row_map).expect("unable to serialize row map");
+
+    assert_eq!(
+        json,
+        r#"{"id":{"I64":7},"name":{"String":"Dave"},"nickname":null}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<RowMap>(&json).expect("unable to deserialize row map"),
+        row_map
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_row_map_deserialize_keeps_duplicate_names() {
+    use bedrock_orm::{domain::OwnedValueUnion, query_execution::RowMap};
+
+    let row_map = serde_json::from_str::<RowMap>(r#"{"id":{"I64":1},"name":null,"id":{"I64":2}}"#)
+        .expect("unable to deserialize row map");
+
+    assert_eq!(
+        row_map,
+        [
+            ("id", Some(OwnedValueUnion::I64(1))),
+            ("name", None),
+            ("id", Some(OwnedValueUnion::I64(2))),
+        ]
+        .into_iter()
+        .collect()
+    );
+    assert_eq!(row_map.len(), 3);
+    assert_eq!(
+        serde_json::to_string(&row_map).expect("unable to serialize row map"),
+        r#"{"id":{"I64":1},"name":null,"id":{"I64":2}}"#
+    );
+}
+
+#[test]
+fn test_none_clears_previous_binding() {
+    let connection = connect_memory();
+    create_table_users(&connection);
+
+    let mut query = SqliteQuery::new_without_results(
+        &connection,
+        "INSERT INTO users VALUES (:name, :age, :active, :role, :born, :nickname, :avatar)",
+    )
+    .expect("unable to create query");
+
+    let mut users = vec![
+        User {
+            name: "Alice".to_owned(),
+            age: 30,
+            active: true,
+            role: Role::Admin,
+            born: NaiveDate::from_ymd_opt(1993, 4, 5).expect("invalid date"),
+            nickname: Some("Al".to_owned()),
+            avatar: Some(vec![1, 2, 3]),
+        },
+        User {
+            name: "Bob".to_owned(),
+            age: 41,
+            active: false,
+            role: Role::Member,
+            born: NaiveDate::from_ymd_opt(1982, 11, 30).expect("invalid date"),
+            nickname: None,
+            avatar: None,
+        },
+    ];
+
+    for user in &users {
+        query.reset().expect("unable to reset query");
+        query
+            .inject_serialized(user)
+            .expect("unable to inject parameters");
+        connection
+            .execute_without_results(&mut query)
+            .expect("unable to execute query");
+    }
+
+    let mut selected = select_users::<User>(&connection).expect("unable to select users");
+    selected.sort_by(|left, right| left.name.cmp(&right.name));
+    users.sort_by(|left, right| left.name.cmp(&right.name));
+
+    assert_eq!(selected, users);
+}
+
+#[test]
+fn test_sequence_parameters_bind_lists() {
+    #[derive(Serialize)]
+    struct Filter {
+        names: Vec<String>,
+    }
+
+    let connection = connect_memory();
+    create_table_users(&connection);
+
+    for (name, age) in [("Alice", "30"), ("Bob", "41"), ("Carol", "25")] {
+        let parameters = BTreeMap::from([
+            ("name", name),
+            ("age", age),
+            ("active", "true"),
+            ("role", "Member"),
+            ("born", "1990-01-01"),
+        ]);
+
+        insert_user(&connection, &parameters).expect("unable to insert user");
+    }
+
+    let mut query = SqliteQuery::new_with_iterator(
+        &connection,
+        "SELECT * FROM users WHERE name IN (SELECT value FROM json_each(:names)) ORDER BY name",
+    )
+    .expect("unable to create query");
+    query
+        .inject_serialized(&Filter {
+            names: vec!["Carol".to_owned(), "Alice".to_owned()],
+        })
+        .expect("unable to inject parameters");
+
+    let names = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| from_row::<User, _>(&row).map(|user| user.name))
+        .collect::<bedrock_orm::Result<Vec<_>>>()
+        .expect("unable to read users");
+
+    assert_eq!(names, ["Alice", "Carol"]);
+}
+
+#[test]
+fn test_empty_sequence_parameter_binds_empty_list() {
+    #[derive(Serialize)]
+    struct Filter {
+        ids: Vec<u8>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Summary {
+        kind: String,
+        count: i64,
+    }
+
+    let connection = connect_memory();
+
+    let mut query = SqliteQuery::new_with_iterator(
+        &connection,
+        "SELECT typeof(:ids) AS kind, (SELECT count(*) FROM json_each(:ids)) AS count",
+    )
+    .expect("unable to create query");
+    query
+        .inject_serialized(&Filter { ids: Vec::new() })
+        .expect("unable to inject parameters");
+
+    let rows = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| from_row::<Summary, _>(&row))
+        .collect::<bedrock_orm::Result<Vec<_>>>()
+        .expect("unable to read rows");
+
+    assert_eq!(
+        rows,
+        [Summary {
+            kind: "text".to_owned(),
+            count: 0,
+        }]
+    );
+}
+
+#[test]
+fn test_hand_written_from_row_for_deserializable_type() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Greeting {
+        text: String,
+    }
+
+    impl FromRow for Greeting {
+        fn from_row<Row: ListFeatures<Identifier = String>>(
+            row: &Row,
+        ) -> bedrock_orm::Result<Self> {
+            let name: String = row.take_sql_value(&"name".to_owned())?;
+
+            Ok(Self {
+                text: format!("Hello, {name}!"),
+            })
+        }
+    }
+
+    let connection = connect_memory();
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT 'Alice' AS name")
+        .expect("unable to create query");
+    let greetings = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| Greeting::from_row(&row))
+        .collect::<bedrock_orm::Result<Vec<_>>>()
+        .expect("unable to read rows");
+
+    assert_eq!(
+        greetings,
+        [Greeting {
+            text: "Hello, Alice!".to_owned()
+        }]
+    );
+}
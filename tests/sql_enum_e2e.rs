@@ -0,0 +1,180 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::{DataType, OwnedValueUnion, SqlEnum, SqlType},
+    query_building::{CreateTableQuery, Dialect},
+    query_execution::{ExecuteQuery, InjectFeatures, TakeFeatures},
+    sql_enum, Error,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Status {
+    Active,
+    Suspended,
+    Closed,
+}
+
+sql_enum!(Status as String {
+    Active => "active",
+    Suspended => "suspended",
+    Closed => "closed",
+});
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Priority {
+    Low,
+    High,
+}
+
+sql_enum!(Priority as I64 {
+    Low => 1,
+    High => 10,
+});
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute(connection: &SqliteConnection, query_text: &str) -> bedrock_orm::Result<()> {
+    let mut query = SqliteQuery::new_without_results(connection, query_text)?;
+
+    connection.execute_without_results(&mut query)
+}
+
+fn create_tickets(connection: &SqliteConnection) {
+    let query = CreateTableQuery::new("tickets")
+        .enum_column::<Status>("status")
+        .enum_column::<Priority>("priority")
+        .render(Dialect::Sqlite)
+        .expect("unable to render query");
+
+    execute(connection, query.text()).expect("unable to create table");
+}
+
+fn insert_ticket(
+    connection: &SqliteConnection,
+    status: &Status,
+    priority: &Priority,
+) -> bedrock_orm::Result<()> {
+    let mut query = SqliteQuery::new_without_results(
+        connection,
+        "INSERT INTO tickets VALUES (:status, :priority)",
+    )?;
+
+    query.inject_sql_value(&"status".to_owned(), status)?;
+    query.inject_sql_value(&"priority".to_owned(), priority)?;
+
+    connection.execute_without_results(&mut query)
+}
+
+fn select_tickets(connection: &SqliteConnection) -> bedrock_orm::Result<Vec<(Status, Priority)>> {
+    let mut query = SqliteQuery::new_with_iterator(connection, "SELECT * FROM tickets")?;
+
+    let tickets = connection
+        .execute_with_iterator(&mut query)?
+        .map(|row| {
+            Ok((
+                row.take_sql_value(&"status".to_owned())?,
+                row.take_sql_value(&"priority".to_owned())?,
+            ))
+        })
+        .collect();
+
+    tickets
+}
+
+#[test]
+fn test_enum_mapping() {
+    assert_eq!(Status::data_type(), DataType::String);
+    assert_eq!(
+        Status::Suspended.to_sql_value(),
+        OwnedValueUnion::String("suspended".to_owned())
+    );
+    assert_eq!(
+        Priority::from_sql_value(OwnedValueUnion::I64(10)).expect("unable to convert value"),
+        Priority::High
+    );
+    assert_eq!(
+        Priority::allowed_values(),
+        vec![OwnedValueUnion::I64(1), OwnedValueUnion::I64(10)]
+    );
+    assert!(matches!(
+        Status::from_sql_value(OwnedValueUnion::I64(1)),
+        Err(Error::ValueCannotBeAccessedAsRequestedType { .. })
+    ));
+}
+
+#[test]
+fn test_enum_round_trip() {
+    let connection = connect_memory();
+    create_tickets(&connection);
+
+    insert_ticket(&connection, &Status::Active, &Priority::High).expect("unable to insert");
+    insert_ticket(&connection, &Status::Closed, &Priority::Low).expect("unable to insert");
+
+    assert_eq!(
+        select_tickets(&connection).expect("unable to select tickets"),
+        vec![
+            (Status::Active, Priority::High),
+            (Status::Closed, Priority::Low)
+        ]
+    );
+}
+
+#[test]
+fn test_enum_unknown_value() {
+    let connection = connect_memory();
+    execute(
+        &connection,
+        "CREATE TABLE tickets (status TEXT, priority INTEGER)",
+    )
+    .expect("unable to create table");
+    execute(&connection, "INSERT INTO tickets VALUES ('archived', 1)").expect("unable to insert");
+
+    match select_tickets(&connection) {
+        Err(Error::UnknownEnumValue { enum_name, value }) => {
+            assert_eq!(enum_name, "Status");
+            assert_eq!(value, "archived");
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+}
+
+#[test]
+fn test_enum_check_constraint() {
+    assert_eq!(
+        CreateTableQuery::new("tickets")
+            .enum_column::<Status>("status")
+            .typed_column::<Priority>("priority")
+            .render(Dialect::Postgres)
+            .expect("unable to render query")
+            .text(),
+        "CREATE TABLE \"tickets\" (\"status\" TEXT NOT NULL \
+         CHECK (\"status\" IN ('active', 'suspended', 'closed')), \"priority\" BIGINT NOT NULL)"
+    );
+
+    let connection = connect_memory();
+    create_tickets(&connection);
+
+    assert!(matches!(
+        execute(&connection, "INSERT INTO tickets VALUES ('archived', 1)"),
+        Err(Error::CheckConstraintViolation { .. })
+    ));
+    assert!(matches!(
+        execute(&connection, "INSERT INTO tickets VALUES ('active', 5)"),
+        Err(Error::CheckConstraintViolation { .. })
+    ));
+}
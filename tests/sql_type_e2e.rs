@@ -0,0 +1,226 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+use std::net::IpAddr;
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::{DataType, OwnedValueUnion, SqlType},
+    query_building::{CreateTableQuery, Dialect},
+    query_execution::{ExecuteQuery, FromRow, InjectFeatures, ListFeatures},
+    Error,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct UserId(i64);
+
+impl SqlType for UserId {
+    fn data_type() -> DataType {
+        i64::data_type()
+    }
+
+    fn to_sql_value(&self) -> OwnedValueUnion {
+        self.0.to_sql_value()
+    }
+
+    fn from_sql_value(value: OwnedValueUnion) -> bedrock_orm::Result<Self> {
+        i64::from_sql_value(value).map(Self)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Address(IpAddr);
+
+impl SqlType for Address {
+    fn data_type() -> DataType {
+        DataType::String
+    }
+
+    fn to_sql_value(&self) -> OwnedValueUnion {
+        OwnedValueUnion::String(self.0.to_string())
+    }
+
+    fn from_sql_value(value: OwnedValueUnion) -> bedrock_orm::Result<Self> {
+        String::from_sql_value(value)?
+            .parse()
+            .map(Self)
+            .map_err(|error| Error::InvalidSqlValue {
+                message: format!("{error}"),
+            })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Login {
+    user_id: UserId,
+    address: Address,
+    note: Option<String>,
+}
+
+impl FromRow for Login {
+    fn from_row<Row: ListFeatures<Identifier = String>>(row: &Row) -> bedrock_orm::Result<Self> {
+        Ok(Self {
+            user_id: row.take_sql_value(&"user_id".to_owned())?,
+            address: row.take_sql_value(&"address".to_owned())?,
+            note: row.take_sql_value(&"note".to_owned())?,
+        })
+    }
+}
+
+fn create_logins() -> CreateTableQuery {
+    CreateTableQuery::new("logins")
+        .typed_column::<UserId>("user_id")
+        .typed_column::<Address>("address")
+        .typed_column::<Option<String>>("note")
+        .primary_key(["user_id", "address"])
+}
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn insert_login(connection: &SqliteConnection, login: &Login) {
+    let mut query = SqliteQuery::new_without_results(
+        connection,
+        "INSERT INTO logins VALUES (:user_id, :address, :note)",
+    )
+    .expect("unable to create query");
+
+    query
+        .inject_sql_value(&"user_id".to_owned(), &login.user_id)
+        .expect("unable to inject feature");
+    query
+        .inject_sql_value(&"address".to_owned(), &login.address)
+        .expect("unable to inject feature");
+    query
+        .inject_sql_value(&"note".to_owned(), &login.note)
+        .expect("unable to inject feature");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn select_logins(connection: &SqliteConnection) -> bedrock_orm::Result<Vec<Login>> {
+    let mut query = SqliteQuery::new_with_iterator(connection, "SELECT * FROM logins")?;
+
+    let logins = connection
+        .execute_with_iterator(&mut query)?
+        .map(|row| Login::from_row(&row))
+        .collect();
+
+    logins
+}
+
+#[test]
+fn test_render_create_table() {
+    assert_eq!(
+        create_logins()
+            .render(Dialect::Sqlite)
+            .expect("unable to render query")
+            .text(),
+        "CREATE TABLE \"logins\" (\"user_id\" INTEGER NOT NULL, \"address\" TEXT NOT NULL, \
+         \"note\" TEXT, PRIMARY KEY (\"user_id\", \"address\"))"
+    );
+    assert_eq!(
+        CreateTableQuery::new("events")
+            .column("id", DataType::I64)
+            .column("at", DataType::DateTimeUtc.nullable())
+            .if_not_exists()
+            .render(Dialect::Postgres)
+            .expect("unable to render query")
+            .text(),
+        "CREATE TABLE IF NOT EXISTS \"events\" (\"id\" BIGINT NOT NULL, \"at\" TIMESTAMPTZ)"
+    );
+    assert!(matches!(
+        CreateTableQuery::new("empty").render(Dialect::MySql),
+        Err(Error::InvalidQuery { .. })
+    ));
+}
+
+#[test]
+fn test_custom_types_round_trip() {
+    let connection = connect_memory();
+    execute_ok(
+        &connection,
+        create_logins()
+            .render(Dialect::Sqlite)
+            .expect("unable to render query")
+            .text(),
+    );
+
+    let logins = vec![
+        Login {
+            user_id: UserId(1),
+            address: Address("192.168.0.1".parse().expect("invalid address")),
+            note: Some("office".to_owned()),
+        },
+        Login {
+            user_id: UserId(2),
+            address: Address("::1".parse().expect("invalid address")),
+            note: None,
+        },
+    ];
+
+    for login in &logins {
+        insert_login(&connection, login);
+    }
+
+    assert_eq!(
+        select_logins(&connection).expect("unable to select logins"),
+        logins
+    );
+}
+
+#[test]
+fn test_custom_type_decode_error() {
+    let connection = connect_memory();
+    execute_ok(
+        &connection,
+        "CREATE TABLE logins (user_id INTEGER, address TEXT, note TEXT)",
+    );
+    execute_ok(
+        &connection,
+        "INSERT INTO logins VALUES (1, 'not an address', NULL)",
+    );
+
+    assert!(matches!(
+        select_logins(&connection),
+        Err(Error::InvalidSqlValue { .. })
+    ));
+}
+
+#[test]
+fn test_missing_required_value() {
+    let connection = connect_memory();
+    execute_ok(
+        &connection,
+        "CREATE TABLE logins (user_id INTEGER, address TEXT, note TEXT)",
+    );
+    execute_ok(&connection, "INSERT INTO logins VALUES (NULL, '::1', NULL)");
+
+    assert!(matches!(
+        select_logins(&connection),
+        Err(Error::ValueCannotBeAccessedAsRequestedType { .. })
+    ));
+}
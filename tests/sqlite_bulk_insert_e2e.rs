@@ -0,0 +1,287 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::{sync::mpsc, thread, time::Duration};
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::ValueUnion,
+    query_execution::{BulkInsert, ExecuteQuery, QueryResult, TakeFeatures},
+    Error,
+};
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn create_table_users(connection: &SqliteConnection) {
+    execute_ok(
+        connection,
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+    );
+}
+
+fn select_users_count(connection: &SqliteConnection) -> i64 {
+    let mut query =
+        SqliteQuery::new_with_iterator(connection, "SELECT COUNT(*) as count FROM users")
+            .expect("unable to create query");
+
+    let query_result = connection
+        .execute(&mut query)
+        .expect("unable to execute query");
+
+    if let QueryResult::Iterator { mut row_iterator } = query_result {
+        TryInto::<i64>::try_into(
+            row_iterator
+                .next()
+                .expect("unable to get first row")
+                .take_feature(&"count".to_owned())
+                .expect("unable to take feature")
+                .expect("feature cannot be null"),
+        )
+        .expect("unable to convert feature")
+    } else {
+        panic!("query result is not an iterator");
+    }
+}
+
+#[test]
+fn test_bulk_insert_many_rows() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+
+    let names = (0..10_000)
+        .map(|id| format!("user {id}"))
+        .collect::<Vec<_>>();
+    let rows = names
+        .iter()
+        .enumerate()
+        .map(|(id, name)| {
+            [
+                ValueUnion::I64(id as i64),
+                ValueUnion::String(name),
+                ValueUnion::U32(id as u32 % 100),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        connection
+            .bulk_insert("users", &["id", "name", "age"], rows)
+            .expect("unable to bulk insert"),
+        10_000
+    );
+    assert_eq!(select_users_count(&connection), 10_000);
+}
+
+#[test]
+fn test_bulk_insert_no_rows() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+
+    assert_eq!(
+        connection
+            .bulk_insert("users", &["id"], Vec::<Vec<ValueUnion>>::new())
+            .expect("unable to bulk insert"),
+        0
+    );
+}
+
+#[test]
+fn test_bulk_insert_row_length_mismatch_rolls_back() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+
+    let rows = (0..2_000)
+        .map(|id| {
+            if id == 1_500 {
+                vec![ValueUnion::I64(id)]
+            } else {
+                vec![ValueUnion::I64(id), ValueUnion::I64(id)]
+            }
+        })
+        .collect::<Vec<_>>();
+
+    assert!(matches!(
+        connection.bulk_insert("users", &["id", "age"], rows),
+        Err(Error::RowLengthMismatch {
+            expected: 2,
+            actual: 1
+        })
+    ));
+    assert_eq!(select_users_count(&connection), 0);
+}
+
+#[test]
+fn test_bulk_insert_constraint_violation_rolls_back() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+
+    let rows = (0..2_000)
+        .map(|id| [ValueUnion::I64(id % 1_500)])
+        .collect::<Vec<_>>();
+
+    assert!(matches!(
+        connection.bulk_insert("users", &["id"], rows),
+        Err(Error::UniqueConstraintViolation { .. })
+    ));
+    assert_eq!(select_users_count(&connection), 0);
+}
+
+#[test]
+fn test_bulk_insert_inside_transaction() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+
+    execute_ok(&connection, "BEGIN");
+
+    connection
+        .bulk_insert("users", &["id"], (0..10).map(|id| [ValueUnion::I64(id)]))
+        .expect("unable to bulk insert");
+
+    execute_ok(&connection, "ROLLBACK");
+
+    assert_eq!(select_users_count(&connection), 0);
+}
+
+#[test]
+fn test_bulk_insert_without_columns() {
+    let connection = connect_memory();
+
+    assert!(matches!(
+        connection.bulk_insert("users", &[], Vec::<Vec<ValueUnion>>::new()),
+        Err(Error::InvalidBulkInsert { .. })
+    ));
+}
+
+#[test]
+fn test_bulk_insert_excludes_other_threads() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+    execute_ok(&connection, "CREATE TABLE events (name TEXT)");
+
+    let (started_sender, started) = mpsc::channel();
+    let other_connection = connection.clone();
+    let other_thread = thread::spawn(move || {
+        started.recv().expect("bulk insert did not start");
+        execute_ok(
+            &other_connection,
+            "INSERT INTO events VALUES ('other thread')",
+        );
+    });
+
+    // The other thread's insert is issued while the savepoint is open and the batch then fails, so
+    // the insert would be rolled back with it if it were not held off until the batch is done
+    let rows = (0..2_000).map(|id| {
+        if id == 1_000 {
+            started_sender.send(()).expect("other thread stopped");
+            thread::sleep(Duration::from_millis(100));
+            vec![ValueUnion::I64(id)]
+        } else {
+            vec![ValueUnion::I64(id), ValueUnion::I64(id)]
+        }
+    });
+
+    assert!(matches!(
+        connection.bulk_insert("users", &["id", "age"], rows),
+        Err(Error::RowLengthMismatch { .. })
+    ));
+
+    other_thread.join().expect("other thread panicked");
+
+    let mut query =
+        SqliteQuery::new_with_iterator(&connection, "SELECT COUNT(*) AS count FROM events")
+            .expect("unable to create query");
+    let count = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("unable to get first row")
+        .take_sql_value::<i64>(&"count".to_owned())
+        .expect("unable to take feature");
+
+    assert_eq!(count, 1);
+    assert_eq!(select_users_count(&connection), 0);
+}
+
+#[test]
+fn test_bulk_insert_waits_for_statements_in_flight() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+    execute_ok(&connection, "CREATE TABLE events (name TEXT)");
+
+    // Inserts from other threads keep running while the bulk inserts fail over and over, so any
+    // insert that slipped into a savepoint would be rolled back along with the failing batch
+    let other_threads = (0..4)
+        .map(|_| {
+            let other_connection = connection.clone();
+
+            thread::spawn(move || {
+                for _ in 0..250 {
+                    execute_ok(
+                        &other_connection,
+                        "INSERT INTO events VALUES ('other thread')",
+                    );
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    while !other_threads
+        .iter()
+        .all(|other_thread| other_thread.is_finished())
+    {
+        let rows = (0..100).map(|id| vec![ValueUnion::I64(id % 99), ValueUnion::I64(id)]);
+
+        assert!(matches!(
+            connection.bulk_insert("users", &["id", "age"], rows),
+            Err(Error::UniqueConstraintViolation { .. })
+        ));
+    }
+
+    for other_thread in other_threads {
+        other_thread.join().expect("other thread panicked");
+    }
+
+    let mut query =
+        SqliteQuery::new_with_iterator(&connection, "SELECT COUNT(*) AS count FROM events")
+            .expect("unable to create query");
+    let count = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("unable to get first row")
+        .take_sql_value::<i64>(&"count".to_owned())
+        .expect("unable to take feature");
+
+    assert_eq!(count, 1_000);
+    assert_eq!(select_users_count(&connection), 0);
+}
@@ -0,0 +1,304 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::{thread, time::Duration};
+
+use bedrock_orm::{
+    database_providers::{AsyncSqliteConnection, AsyncSqliteQuery, SqliteConnection, SqliteQuery},
+    domain::ValueUnion,
+    query_execution::{AsyncExecuteQuery, ExecuteQuery, InjectFeatures, RowStream, TakeFeatures},
+    Error,
+};
+use futures::{executor::block_on, StreamExt};
+use tempfile::TempDir;
+
+// Never finishes on its own, so it only returns once it is interrupted
+const ENDLESS_QUERY: &str =
+    "WITH RECURSIVE counter(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM counter) \
+     SELECT COUNT(*) AS count FROM counter";
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to prepare query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn create_table_numbers(connection: &SqliteConnection) {
+    execute_ok(connection, "CREATE TABLE numbers (value INTEGER)");
+    execute_ok(connection, "INSERT INTO numbers VALUES (1), (2), (3)");
+}
+
+fn select_values(connection: &SqliteConnection, query: &mut SqliteQuery) -> Vec<i64> {
+    connection
+        .execute_with_iterator(query)
+        .expect("unable to execute query")
+        .map(|row| {
+            row.take_feature(&"value".to_owned())
+                .expect("unable to take value")
+                .expect("value is null")
+                .try_into()
+                .expect("value is not an integer")
+        })
+        .collect()
+}
+
+#[test]
+fn test_cancel_from_another_thread() {
+    let connection = connect_memory();
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, ENDLESS_QUERY)
+        .expect("unable to prepare query");
+    let mut rows = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query");
+
+    let cancel_handle = rows.cancel_handle();
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancel_handle.cancel();
+    });
+
+    let row = rows.next().expect("expected a row or an error");
+
+    canceller.join().expect("canceller thread panicked");
+
+    assert!(matches!(
+        row.map_err(Error::from),
+        Err(Error::QueryCancelled)
+    ));
+    assert!(rows.next().is_none());
+}
+
+#[test]
+fn test_cancel_before_iterating() {
+    let connection = connect_memory();
+    create_table_numbers(&connection);
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT value FROM numbers")
+        .expect("unable to prepare query");
+    let mut rows = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query");
+
+    rows.cancel_handle().cancel();
+
+    assert!(matches!(
+        rows.next().expect("expected an error").map_err(Error::from),
+        Err(Error::QueryCancelled)
+    ));
+}
+
+#[test]
+fn test_cancel_only_affects_its_own_stream() {
+    let connection = connect_memory();
+    create_table_numbers(&connection);
+
+    let mut endless_query = SqliteQuery::new_with_iterator(&connection, ENDLESS_QUERY)
+        .expect("unable to prepare query");
+    let mut endless_rows = connection
+        .execute_with_iterator(&mut endless_query)
+        .expect("unable to execute query");
+    let cancel_handle = endless_rows.cancel_handle();
+
+    let other_connection = connection.clone();
+    let other = thread::spawn(move || {
+        let mut query =
+            SqliteQuery::new_with_iterator(&other_connection, "SELECT value FROM numbers")
+                .expect("unable to prepare query");
+
+        select_values(&other_connection, &mut query)
+    });
+
+    thread::sleep(Duration::from_millis(50));
+    cancel_handle.cancel();
+
+    assert!(matches!(
+        endless_rows
+            .next()
+            .expect("expected an error")
+            .map_err(Error::from),
+        Err(Error::QueryCancelled)
+    ));
+    assert_eq!(other.join().expect("other thread panicked"), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_timeout() {
+    let connection = connect_memory();
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, ENDLESS_QUERY)
+        .expect("unable to prepare query");
+    query.set_timeout(Some(Duration::from_millis(50)));
+
+    let mut rows = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query");
+
+    assert!(matches!(
+        rows.next().expect("expected an error").map_err(Error::from),
+        Err(Error::QueryTimedOut)
+    ));
+
+    let mut query = SqliteQuery::new_without_results(&connection, ENDLESS_QUERY)
+        .expect("unable to prepare query");
+    query.set_timeout(Some(Duration::from_millis(50)));
+
+    assert!(matches!(
+        connection.execute_without_results(&mut query),
+        Err(Error::QueryTimedOut)
+    ));
+}
+
+#[test]
+fn test_timeout_not_reached() {
+    let connection = connect_memory();
+    create_table_numbers(&connection);
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT value FROM numbers")
+        .expect("unable to prepare query");
+    query.set_timeout(Some(Duration::from_secs(60)));
+
+    assert_eq!(select_values(&connection, &mut query), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_early_drop_resets_statement() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("cancellation.sqlite3");
+
+    let reader = SqliteConnection::connect_file(&path).expect("unable to connect");
+    let writer = SqliteConnection::connect_file(&path).expect("unable to connect");
+    create_table_numbers(&reader);
+
+    let mut query = SqliteQuery::new_with_iterator(&reader, "SELECT value FROM numbers")
+        .expect("unable to prepare query");
+
+    {
+        let mut rows = reader
+            .execute_with_iterator(&mut query)
+            .expect("unable to execute query");
+
+        assert!(rows.next().is_some());
+    }
+
+    // The abandoned read would otherwise keep its shared lock and block the write
+    execute_ok(&writer, "INSERT INTO numbers VALUES (4)");
+
+    assert_eq!(select_values(&reader, &mut query), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_async_drop_cancels_scan() {
+    block_on(async {
+        let connection = AsyncSqliteConnection::connect_memory()
+            .await
+            .expect("unable to connect to sqlite database in memory");
+
+        let mut query = AsyncSqliteQuery::new_with_iterator(ENDLESS_QUERY);
+        let rows = connection
+            .execute_with_iterator(&mut query)
+            .await
+            .expect("unable to execute query");
+
+        drop(rows);
+
+        // The worker runs one job at a time, so this only completes once the scan is interrupted
+        let mut query = AsyncSqliteQuery::new_with_iterator("SELECT 1 AS value");
+        let mut rows = connection
+            .execute_with_iterator(&mut query)
+            .await
+            .expect("unable to execute query");
+
+        assert!(rows.next().await.expect("expected a row").is_ok());
+    });
+}
+
+#[test]
+fn test_async_timeout() {
+    block_on(async {
+        let connection = AsyncSqliteConnection::connect_memory()
+            .await
+            .expect("unable to connect to sqlite database in memory");
+
+        let mut query = AsyncSqliteQuery::new_without_results(ENDLESS_QUERY);
+        query.set_timeout(Some(Duration::from_millis(50)));
+
+        assert!(matches!(
+            connection.execute_without_results(&mut query).await,
+            Err(Error::QueryTimedOut)
+        ));
+    });
+}
+
+#[test]
+fn test_cancel_query_without_results() {
+    let connection = connect_memory();
+
+    let mut query = SqliteQuery::new_without_results(&connection, ENDLESS_QUERY)
+        .expect("unable to prepare query");
+
+    let cancel_handle = query.cancel_handle();
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancel_handle.cancel();
+    });
+
+    let result = connection.execute_without_results(&mut query);
+
+    canceller.join().expect("canceller thread panicked");
+
+    assert!(matches!(result, Err(Error::QueryCancelled)));
+}
+
+#[test]
+fn test_cancelled_query_runs_again() {
+    let connection = connect_memory();
+
+    let mut query = SqliteQuery::new_with_change_count(
+        &connection,
+        "WITH RECURSIVE counter(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM counter \
+         WHERE x < :limit) SELECT COUNT(*) FROM counter",
+    )
+    .expect("unable to prepare query");
+    query
+        .inject_feature(&"limit".to_owned(), &ValueUnion::I64(i64::MAX))
+        .expect("unable to inject parameter");
+
+    let cancel_handle = query.cancel_handle();
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancel_handle.cancel();
+    });
+
+    let result = connection.execute_with_change_count(&mut query);
+
+    canceller.join().expect("canceller thread panicked");
+
+    assert!(matches!(result, Err(Error::QueryCancelled)));
+
+    query.reset().expect("unable to reset query");
+    query
+        .inject_feature(&"limit".to_owned(), &ValueUnion::I64(10))
+        .expect("unable to inject parameter");
+
+    assert!(connection.execute_with_change_count(&mut query).is_ok());
+}
@@ -13,12 +13,15 @@
 // You should have received a copy of the GNU General Public License along with Bedrock ORM. If
 // not, see <https://www.gnu.org/licenses/>.
 
+// These tests predate the lints below and are kept as originally written
+#![allow(clippy::clone_on_copy, clippy::needless_borrow)]
+
 use bedrock_orm::{
     database_providers::{SqliteConnection, SqliteQuery},
     query_execution::{ExecuteQuery, QueryResult, TakeFeatures},
 };
 
-fn connect_memory<'connection>() -> SqliteConnection<'connection> {
+fn connect_memory() -> SqliteConnection {
     SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
 }
 
@@ -122,3 +125,98 @@ fn test_users_empty() {
     assert!(select_users(&connection).is_empty());
     assert_eq!(select_users_count(&connection), 0);
 }
+
+struct UserRepository {
+    connection: SqliteConnection,
+    select_users_count: SqliteQuery,
+}
+
+#[test]
+fn test_connection_and_query_stored_together() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+
+    let repository = UserRepository {
+        select_users_count: SqliteQuery::new_with_iterator(
+            &connection,
+            "SELECT COUNT(*) as count FROM users",
+        )
+        .expect("unable to create query"),
+        connection,
+    };
+
+    let mut repository = std::thread::spawn(move || repository)
+        .join()
+        .expect("thread panicked");
+
+    let query_result = repository
+        .connection
+        .execute(&mut repository.select_users_count)
+        .expect("unable to execute query");
+
+    if let QueryResult::Iterator { mut row_iterator } = query_result {
+        let row = row_iterator.next().expect("unable to get first row");
+
+        assert_eq!(
+            TryInto::<i64>::try_into(
+                row.take_feature(&"count".to_owned())
+                    .expect("unable to take feature")
+                    .expect("feature cannot be null"),
+            )
+            .expect("unable to convert feature"),
+            0
+        );
+    } else {
+        panic!("query result is not an iterator");
+    }
+}
+
+#[test]
+fn test_connection_shared_between_threads() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+
+    let handles = (0..4)
+        .map(|age| {
+            let connection = connection.clone();
+
+            std::thread::spawn(move || {
+                let mut query = SqliteQuery::new_with_change_count(
+                    &connection,
+                    &format!("INSERT INTO users VALUES ('Bob', {age})"),
+                )
+                .expect("unable to create query");
+
+                connection
+                    .execute(&mut query)
+                    .expect("unable to execute query");
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        handle.join().expect("inserting thread panicked");
+    }
+
+    assert_eq!(select_users_count(&connection), 4);
+}
+
+#[test]
+fn test_query_outlives_connection_handle() {
+    let connection = connect_memory();
+
+    create_table_users(&connection);
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT name, age FROM users")
+        .expect("unable to create query");
+
+    let executor = connection.clone();
+    drop(connection);
+
+    assert!(matches!(
+        executor.execute(&mut query),
+        Ok(QueryResult::Iterator { .. })
+    ));
+}
@@ -0,0 +1,161 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    query_execution::ExecuteQuery,
+    Error,
+};
+use tempfile::TempDir;
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute(connection: &SqliteConnection, query_text: &str) -> bedrock_orm::Result<usize> {
+    let mut query = SqliteQuery::new_with_change_count(connection, query_text)?;
+
+    connection.execute_with_change_count(&mut query)
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    execute(connection, query_text).expect("unable to execute query");
+}
+
+#[test]
+fn test_unique_constraint_violation() {
+    let connection = connect_memory();
+
+    execute_ok(
+        &connection,
+        "CREATE TABLE users (email TEXT, org TEXT, UNIQUE (email, org))",
+    );
+    execute_ok(&connection, "INSERT INTO users VALUES ('a@b.c', 'x')");
+
+    match execute(&connection, "INSERT INTO users VALUES ('a@b.c', 'x')") {
+        Err(Error::UniqueConstraintViolation {
+            constraint,
+            columns,
+            ..
+        }) => {
+            assert_eq!(constraint, None);
+            assert_eq!(columns, vec!["users.email", "users.org"]);
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
+
+#[test]
+fn test_primary_key_violation() {
+    let connection = connect_memory();
+
+    execute_ok(&connection, "CREATE TABLE users (id INTEGER PRIMARY KEY)");
+    execute_ok(&connection, "INSERT INTO users VALUES (1)");
+
+    assert!(matches!(
+        execute(&connection, "INSERT INTO users VALUES (1)"),
+        Err(Error::UniqueConstraintViolation { columns, .. }) if columns == vec!["users.id"]
+    ));
+}
+
+#[test]
+fn test_foreign_key_constraint_violation() {
+    let connection = connect_memory();
+
+    execute_ok(&connection, "PRAGMA foreign_keys = ON");
+    execute_ok(&connection, "CREATE TABLE orgs (id INTEGER PRIMARY KEY)");
+    execute_ok(
+        &connection,
+        "CREATE TABLE users (org_id INTEGER REFERENCES orgs (id))",
+    );
+
+    assert!(matches!(
+        execute(&connection, "INSERT INTO users VALUES (1)"),
+        Err(Error::ForeignKeyConstraintViolation { .. })
+    ));
+}
+
+#[test]
+fn test_not_null_constraint_violation() {
+    let connection = connect_memory();
+
+    execute_ok(&connection, "CREATE TABLE users (name TEXT NOT NULL)");
+
+    assert!(matches!(
+        execute(&connection, "INSERT INTO users VALUES (NULL)"),
+        Err(Error::NotNullConstraintViolation { column: Some(column), .. }) if column == "users.name"
+    ));
+}
+
+#[test]
+fn test_check_constraint_violation() {
+    let connection = connect_memory();
+
+    execute_ok(
+        &connection,
+        "CREATE TABLE users (age INTEGER CONSTRAINT age_positive CHECK (age > 0))",
+    );
+
+    assert!(matches!(
+        execute(&connection, "INSERT INTO users VALUES (-1)"),
+        Err(Error::CheckConstraintViolation { constraint: Some(constraint), .. })
+            if constraint == "age_positive"
+    ));
+}
+
+#[test]
+fn test_schema_mismatch() {
+    let connection = connect_memory();
+
+    execute_ok(&connection, "CREATE TABLE users (name TEXT)");
+
+    assert!(matches!(
+        execute(&connection, "INSERT INTO missing VALUES (1)"),
+        Err(Error::SchemaMismatch { object: Some(object), .. }) if object == "missing"
+    ));
+    assert!(matches!(
+        execute(&connection, "INSERT INTO users (age) VALUES (1)"),
+        Err(Error::SchemaMismatch { object: Some(object), .. }) if object == "age"
+    ));
+}
+
+#[test]
+fn test_read_only() {
+    let connection = connect_memory();
+
+    execute_ok(&connection, "CREATE TABLE users (name TEXT)");
+    execute_ok(&connection, "PRAGMA query_only = ON");
+
+    assert!(matches!(
+        execute(&connection, "INSERT INTO users VALUES ('Bob')"),
+        Err(Error::DatabaseReadOnly { .. })
+    ));
+}
+
+#[test]
+fn test_busy() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let path = directory.path().join("busy.sqlite3");
+    let first = SqliteConnection::connect_file(&path).expect("unable to connect");
+    let second = SqliteConnection::connect_file(&path).expect("unable to connect");
+
+    execute_ok(&first, "CREATE TABLE users (name TEXT)");
+    execute_ok(&first, "BEGIN EXCLUSIVE");
+
+    assert!(matches!(
+        execute(&second, "INSERT INTO users VALUES ('Bob')"),
+        Err(Error::DatabaseBusy { .. })
+    ));
+}
@@ -0,0 +1,201 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::{thread, time::Duration};
+
+use bedrock_orm::{
+    connection_pooling::{Pool, PoolOptions, PoolStatus},
+    database_providers::{SqliteConnection, SqliteConnectionManager, SqliteQuery},
+    query_execution::{ExecuteQuery, QueryResult},
+    Error,
+};
+use tempfile::TempDir;
+
+fn create_pool(directory: &TempDir, options: PoolOptions) -> Pool<SqliteConnectionManager> {
+    Pool::new(
+        SqliteConnectionManager::file(directory.path().join("pool.sqlite3")),
+        options,
+    )
+    .expect("unable to create pool")
+}
+
+fn execute_change_count(connection: &SqliteConnection, query_text: &str) -> usize {
+    let mut query =
+        SqliteQuery::new_with_change_count(connection, query_text).expect("unable to create query");
+
+    let query_result = connection
+        .execute(&mut query)
+        .expect("unable to execute query");
+
+    match query_result {
+        QueryResult::ChangeCount { count } => count,
+        _ => panic!("query result is not a change count"),
+    }
+}
+
+#[test]
+fn test_pool_min_size() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let pool = create_pool(&directory, PoolOptions::new().min_size(2).max_size(4));
+
+    assert_eq!(
+        pool.status(),
+        PoolStatus {
+            connections: 2,
+            idle_connections: 2
+        }
+    );
+}
+
+#[test]
+fn test_pool_invalid_options() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+
+    assert!(matches!(
+        Pool::new(
+            SqliteConnectionManager::file(directory.path().join("pool.sqlite3")),
+            PoolOptions::new().min_size(3).max_size(2),
+        ),
+        Err(Error::InvalidPoolOptions { .. })
+    ));
+}
+
+#[test]
+fn test_pool_connections_share_file() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let pool = create_pool(&directory, PoolOptions::new().max_size(2));
+
+    let first = pool.get().expect("unable to get connection");
+    let second = pool.get().expect("unable to get connection");
+
+    execute_change_count(&first, "CREATE TABLE users (name TEXT, age INTEGER)");
+
+    assert_eq!(
+        execute_change_count(&second, "INSERT INTO users VALUES ('Alice', 30)"),
+        1
+    );
+}
+
+#[test]
+fn test_pool_reuses_released_connection() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let pool = create_pool(&directory, PoolOptions::new().max_size(1));
+
+    drop(pool.get().expect("unable to get connection"));
+    drop(pool.get().expect("unable to get connection"));
+
+    assert_eq!(
+        pool.status(),
+        PoolStatus {
+            connections: 1,
+            idle_connections: 1
+        }
+    );
+}
+
+#[test]
+fn test_pool_acquire_timeout() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let pool = create_pool(
+        &directory,
+        PoolOptions::new()
+            .max_size(1)
+            .acquire_timeout(Duration::from_millis(50)),
+    );
+
+    let _connection = pool.get().expect("unable to get connection");
+
+    assert!(matches!(pool.get(), Err(Error::PoolTimedOut { .. })));
+}
+
+#[test]
+fn test_pool_acquire_waits_for_release() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let pool = create_pool(
+        &directory,
+        PoolOptions::new()
+            .max_size(1)
+            .acquire_timeout(Duration::from_secs(5)),
+    );
+
+    let connection = pool.get().expect("unable to get connection");
+
+    let waiter = {
+        let pool = pool.clone();
+        thread::spawn(move || pool.get().map(|_| ()))
+    };
+
+    thread::sleep(Duration::from_millis(50));
+    drop(connection);
+
+    waiter
+        .join()
+        .expect("waiting thread panicked")
+        .expect("unable to get connection after release");
+}
+
+#[test]
+fn test_pool_max_lifetime() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let pool = create_pool(
+        &directory,
+        PoolOptions::new()
+            .max_size(1)
+            .max_lifetime(Some(Duration::from_millis(20)))
+            .reap_interval(Duration::from_secs(60)),
+    );
+
+    let connection = pool.get().expect("unable to get connection");
+    thread::sleep(Duration::from_millis(40));
+    drop(connection);
+
+    assert_eq!(
+        pool.status(),
+        PoolStatus {
+            connections: 0,
+            idle_connections: 0
+        }
+    );
+}
+
+#[test]
+fn test_pool_reaps_idle_connections() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let pool = create_pool(
+        &directory,
+        PoolOptions::new()
+            .min_size(1)
+            .max_size(3)
+            .idle_timeout(Some(Duration::from_millis(10)))
+            .reap_interval(Duration::from_millis(20)),
+    );
+
+    let first = pool.get().expect("unable to get connection");
+    let second = pool.get().expect("unable to get connection");
+    let third = pool.get().expect("unable to get connection");
+    drop((first, second, third));
+
+    assert_eq!(pool.status().connections, 3);
+
+    thread::sleep(Duration::from_millis(200));
+
+    assert_eq!(
+        pool.status(),
+        PoolStatus {
+            connections: 1,
+            idle_connections: 1
+        }
+    );
+}
@@ -0,0 +1,168 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::{thread, time::Duration};
+
+use bedrock_orm::{
+    database_providers::{RetryMetrics, RetryPolicy, SqliteConnection, SqliteQuery},
+    query_execution::{ExecuteQuery, QueryResult},
+    Error,
+};
+use tempfile::TempDir;
+
+fn connect_pair(directory: &TempDir) -> (SqliteConnection, SqliteConnection) {
+    let path = directory.path().join("retry.sqlite3");
+
+    (
+        SqliteConnection::connect_file(&path).expect("unable to connect"),
+        SqliteConnection::connect_file(&path).expect("unable to connect"),
+    )
+}
+
+fn execute(connection: &SqliteConnection, query_text: &str) -> bedrock_orm::Result<usize> {
+    let mut query = SqliteQuery::new_with_change_count(connection, query_text)?;
+
+    connection.execute_with_change_count(&mut query)
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    execute(connection, query_text).expect("unable to execute query");
+}
+
+fn hold_exclusive_lock(connection: SqliteConnection, duration: Duration) -> thread::JoinHandle<()> {
+    execute_ok(&connection, "BEGIN EXCLUSIVE");
+
+    thread::spawn(move || {
+        thread::sleep(duration);
+        execute_ok(&connection, "COMMIT");
+    })
+}
+
+fn fast_retry_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy::new()
+        .max_attempts(max_attempts)
+        .initial_backoff(Duration::from_millis(5))
+        .max_backoff(Duration::from_millis(20))
+}
+
+#[test]
+fn test_retry_disabled_by_default() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let (first, second) = connect_pair(&directory);
+
+    execute_ok(&first, "CREATE TABLE users (name TEXT)");
+    let holder = hold_exclusive_lock(first, Duration::from_millis(100));
+
+    assert!(matches!(
+        execute(&second, "INSERT INTO users VALUES ('Bob')"),
+        Err(Error::DatabaseBusy { .. })
+    ));
+    assert_eq!(second.retry_metrics(), RetryMetrics::default());
+
+    holder.join().expect("lock holder panicked");
+}
+
+#[test]
+fn test_retry_succeeds_after_lock_released() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let (first, mut second) = connect_pair(&directory);
+
+    second.set_retry_policy(fast_retry_policy(100));
+
+    execute_ok(&first, "CREATE TABLE users (name TEXT)");
+    let holder = hold_exclusive_lock(first, Duration::from_millis(50));
+
+    assert_eq!(
+        execute(&second, "INSERT INTO users VALUES ('Bob')").expect("retry did not succeed"),
+        1
+    );
+
+    let metrics = second.retry_metrics();
+    assert_eq!(metrics.retried_operations, 1);
+    assert!(metrics.retries >= 1);
+    assert_eq!(metrics.exhausted_operations, 0);
+
+    holder.join().expect("lock holder panicked");
+}
+
+#[test]
+fn test_retry_applies_to_iterators() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let (first, mut second) = connect_pair(&directory);
+
+    second.set_retry_policy(fast_retry_policy(100));
+
+    execute_ok(&first, "CREATE TABLE users (name TEXT)");
+
+    // Prepared before the lock is taken so that only stepping through the rows gets retried
+    let mut query = SqliteQuery::new_with_iterator(&second, "SELECT name FROM users")
+        .expect("unable to create query");
+
+    let holder = hold_exclusive_lock(first, Duration::from_millis(50));
+
+    let query_result = second.execute(&mut query).expect("unable to execute query");
+
+    if let QueryResult::Iterator { row_iterator } = query_result {
+        assert!(row_iterator.collect::<Result<Vec<_>, _>>().is_ok());
+    } else {
+        panic!("query result is not an iterator");
+    }
+
+    assert_eq!(second.retry_metrics().retried_operations, 1);
+
+    holder.join().expect("lock holder panicked");
+}
+
+#[test]
+fn test_retry_exhausted() {
+    let directory = TempDir::new().expect("unable to create temporary directory");
+    let (first, mut second) = connect_pair(&directory);
+
+    second.set_retry_policy(fast_retry_policy(3));
+
+    execute_ok(&first, "CREATE TABLE users (name TEXT)");
+    let holder = hold_exclusive_lock(first, Duration::from_millis(500));
+
+    assert!(matches!(
+        execute(&second, "INSERT INTO users VALUES ('Bob')"),
+        Err(Error::DatabaseBusy { .. })
+    ));
+    assert_eq!(
+        second.retry_metrics(),
+        RetryMetrics {
+            retried_operations: 1,
+            retries: 2,
+            exhausted_operations: 1,
+        }
+    );
+
+    holder.join().expect("lock holder panicked");
+}
+
+#[test]
+fn test_backoff_is_capped_for_many_retries() {
+    let policy = RetryPolicy::new().max_backoff(Duration::from_millis(500));
+
+    for retries in [0, 10, 71, 1000, u32::MAX] {
+        assert!(policy.backoff(retries) <= Duration::from_millis(500));
+    }
+
+    let policy = RetryPolicy::new()
+        .max_backoff(Duration::MAX)
+        .multiplier(f64::INFINITY)
+        .jitter(0.0);
+
+    assert_eq!(policy.backoff(u32::MAX), Duration::MAX);
+}
@@ -0,0 +1,151 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::{DataType, OwnedValueUnion, ValueUnion},
+    query_execution::{ExecuteQuery, InjectFeatures, TakeFeatures},
+    Error,
+};
+use chrono::NaiveTime;
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn create_table_shifts(connection: &SqliteConnection) {
+    let mut query = SqliteQuery::new_without_results(
+        connection,
+        "CREATE TABLE shifts (start_time TEXT, length INTEGER)",
+    )
+    .expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn insert_shift(connection: &SqliteConnection, start_time: &NaiveTime, length: &Duration) {
+    let mut query = SqliteQuery::new_without_results(
+        connection,
+        "INSERT INTO shifts VALUES (:start_time, :length)",
+    )
+    .expect("unable to create query");
+
+    query
+        .inject_feature(&"start_time".to_owned(), &ValueUnion::from(start_time))
+        .expect("unable to inject feature");
+    query
+        .inject_feature(&"length".to_owned(), &ValueUnion::from(length))
+        .expect("unable to inject feature");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+#[test]
+fn test_time_and_duration_round_trip() {
+    let connection = connect_memory();
+    create_table_shifts(&connection);
+
+    let start_time = NaiveTime::from_hms_milli_opt(8, 30, 0, 250).expect("invalid time");
+    let length = Duration::from_secs(8 * 3600 + 30 * 60);
+
+    insert_shift(&connection, &start_time, &length);
+
+    let mut query = SqliteQuery::new_with_iterator(
+        &connection,
+        "SELECT start_time, length, typeof(length) AS length_type FROM shifts",
+    )
+    .expect("unable to create query");
+    let row = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("expected a row");
+
+    let stored_start_time = row
+        .take_feature(&"start_time".to_owned())
+        .expect("unable to take feature")
+        .expect("feature cannot be null");
+    let length_type = row
+        .take_feature(&"length_type".to_owned())
+        .expect("unable to take feature")
+        .expect("feature cannot be null");
+
+    assert_eq!(
+        <&String>::try_from(stored_start_time).expect("start time is not text"),
+        "08:30:00.250"
+    );
+    assert_eq!(
+        <&String>::try_from(length_type).expect("type is not text"),
+        "integer"
+    );
+    assert_eq!(
+        row.take_feature_as(&"start_time".to_owned(), &DataType::Time)
+            .expect("unable to convert feature"),
+        Some(OwnedValueUnion::Time(start_time))
+    );
+    assert_eq!(
+        row.take_feature_as(&"length".to_owned(), &DataType::Duration)
+            .expect("unable to convert feature"),
+        Some(OwnedValueUnion::Duration(length))
+    );
+}
+
+#[test]
+fn test_time_without_fraction() {
+    let text = "17:05:09".to_owned();
+
+    assert_eq!(
+        ValueUnion::String(&text)
+            .convert_to(&DataType::Time)
+            .expect("unable to convert"),
+        OwnedValueUnion::Time(NaiveTime::from_hms_opt(17, 5, 9).expect("invalid time"))
+    );
+}
+
+#[test]
+fn test_negative_duration() {
+    assert!(matches!(
+        ValueUnion::I64(-1).convert_to(&DataType::Duration),
+        Err(Error::ValueCannotBeAccessedAsRequestedType {
+            value_type: DataType::I64,
+            requested_type: DataType::Duration,
+        })
+    ));
+}
+
+#[test]
+fn test_time_and_duration_try_from() {
+    let time = NaiveTime::from_hms_opt(23, 59, 59).expect("invalid time");
+    let duration = Duration::from_millis(1500);
+
+    assert_eq!(
+        <&NaiveTime>::try_from(ValueUnion::from(&time)).expect("unable to convert"),
+        &time
+    );
+    assert_eq!(
+        <&Duration>::try_from(ValueUnion::from(&duration)).expect("unable to convert"),
+        &duration
+    );
+    assert!(matches!(
+        <&Duration>::try_from(ValueUnion::from(&time)),
+        Err(Error::ValueCannotBeAccessedAsRequestedType { .. })
+    ));
+}
@@ -0,0 +1,192 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery},
+    domain::ValueUnion,
+    query_building::{Assignment, Dialect, InsertQuery},
+    query_execution::{ExecuteQuery, GetDialect, InjectFeatures, QueryResult, TakeFeatures},
+    Error,
+};
+
+fn upsert_counter() -> InsertQuery {
+    InsertQuery::new("counters", ["name", "count"]).on_conflict_do_update(
+        ["name"],
+        [Assignment::expression(
+            "count",
+            "\"counters\".\"count\" + excluded.\"count\"",
+        )],
+    )
+}
+
+#[test]
+fn test_render_insert() {
+    let rendered = InsertQuery::new("users", ["id", "name"])
+        .render(Dialect::Sqlite)
+        .expect("unable to render query");
+
+    assert_eq!(
+        rendered.text(),
+        "INSERT INTO \"users\" (\"id\", \"name\") VALUES (:id, :name)"
+    );
+    assert_eq!(rendered.parameters(), ["id", "name"]);
+}
+
+#[test]
+fn test_render_upsert_sqlite() {
+    let rendered = InsertQuery::new("users", ["id", "name", "age"])
+        .on_conflict_do_update(
+            ["id"],
+            [
+                Assignment::excluded("name"),
+                Assignment::parameter("age", "new_age"),
+            ],
+        )
+        .render(Dialect::Sqlite)
+        .expect("unable to render query");
+
+    assert_eq!(
+        rendered.text(),
+        "INSERT INTO \"users\" (\"id\", \"name\", \"age\") VALUES (:id, :name, :age) \
+         ON CONFLICT (\"id\") DO UPDATE SET \"name\" = excluded.\"name\", \"age\" = :new_age"
+    );
+    assert_eq!(rendered.parameters(), ["id", "name", "age", "new_age"]);
+}
+
+#[test]
+fn test_render_upsert_postgres() {
+    let rendered = InsertQuery::new("users", ["id", "name", "age"])
+        .on_conflict_do_update(
+            ["id"],
+            [
+                Assignment::excluded("name"),
+                Assignment::parameter("age", "new_age"),
+            ],
+        )
+        .render(Dialect::Postgres)
+        .expect("unable to render query");
+
+    assert_eq!(
+        rendered.text(),
+        "INSERT INTO \"users\" (\"id\", \"name\", \"age\") VALUES ($1, $2, $3) \
+         ON CONFLICT (\"id\") DO UPDATE SET \"name\" = excluded.\"name\", \"age\" = $4"
+    );
+}
+
+#[test]
+fn test_render_upsert_mysql() {
+    let rendered = InsertQuery::new("users", ["id", "name"])
+        .on_conflict_do_update(["id"], [Assignment::excluded("name")])
+        .render(Dialect::MySql)
+        .expect("unable to render query");
+
+    assert_eq!(
+        rendered.text(),
+        "INSERT INTO `users` (`id`, `name`) VALUES (?, ?) \
+         ON DUPLICATE KEY UPDATE `name` = VALUES(`name`)"
+    );
+}
+
+#[test]
+fn test_render_do_nothing() {
+    let query = InsertQuery::new("users", ["id", "name"]).on_conflict_do_nothing(["id"]);
+
+    assert_eq!(
+        query
+            .render(Dialect::Postgres)
+            .expect("unable to render query")
+            .text(),
+        "INSERT INTO \"users\" (\"id\", \"name\") VALUES ($1, $2) ON CONFLICT (\"id\") DO NOTHING"
+    );
+    assert_eq!(
+        query
+            .render(Dialect::MySql)
+            .expect("unable to render query")
+            .text(),
+        "INSERT INTO `users` (`id`, `name`) VALUES (?, ?) ON DUPLICATE KEY UPDATE `id` = `id`"
+    );
+}
+
+#[test]
+fn test_render_do_update_without_target() {
+    assert!(matches!(
+        InsertQuery::new("users", ["id", "name"])
+            .on_conflict_do_update(Vec::<&str>::new(), [Assignment::excluded("name")])
+            .render(Dialect::Sqlite),
+        Err(Error::InvalidQuery { .. })
+    ));
+}
+
+#[test]
+fn test_upsert_sqlite_is_idempotent() {
+    let connection =
+        SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory");
+
+    let mut query = SqliteQuery::new_without_results(
+        &connection,
+        "CREATE TABLE counters (name TEXT PRIMARY KEY, count INTEGER)",
+    )
+    .expect("unable to create query");
+    connection
+        .execute(&mut query)
+        .expect("unable to execute query");
+
+    let rendered = upsert_counter()
+        .render(connection.dialect())
+        .expect("unable to render query");
+    let name = "visits".to_owned();
+
+    for _ in 0..3 {
+        let mut query = SqliteQuery::new_with_change_count(&connection, rendered.text())
+            .expect("unable to create query");
+        query
+            .inject_feature(&"name".to_owned(), &ValueUnion::String(&name))
+            .expect("unable to inject feature");
+        query
+            .inject_feature(&"count".to_owned(), &ValueUnion::I64(2))
+            .expect("unable to inject feature");
+
+        assert_eq!(
+            connection
+                .execute_with_change_count(&mut query)
+                .expect("unable to execute query"),
+            1
+        );
+    }
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT count FROM counters")
+        .expect("unable to create query");
+
+    let query_result = connection
+        .execute(&mut query)
+        .expect("unable to execute query");
+
+    if let QueryResult::Iterator { row_iterator } = query_result {
+        let counts = row_iterator
+            .map(|row| {
+                TryInto::<i64>::try_into(
+                    row.take_feature(&"count".to_owned())
+                        .expect("unable to take feature")
+                        .expect("feature cannot be null"),
+                )
+                .expect("unable to convert feature")
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(counts, vec![6]);
+    } else {
+        panic!("query result is not an iterator");
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+#![cfg(feature = "uuid")]
+
+use bedrock_orm::{
+    database_providers::{SqliteConnection, SqliteQuery, UuidStorage},
+    domain::{DataType, OwnedValueUnion, ValueUnion},
+    query_execution::{ExecuteQuery, InjectFeatures, PageCursor, TakeFeatures},
+};
+use uuid::Uuid;
+
+const ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn insert_id(connection: &SqliteConnection, id: &Uuid) {
+    let mut query = SqliteQuery::new_without_results(connection, "INSERT INTO users VALUES (:id)")
+        .expect("unable to create query");
+
+    query
+        .inject_feature(&"id".to_owned(), &ValueUnion::from(id))
+        .expect("unable to inject feature");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn select_ids(connection: &SqliteConnection) -> Vec<(Uuid, String)> {
+    let mut query =
+        SqliteQuery::new_with_iterator(connection, "SELECT id, typeof(id) AS id_type FROM users")
+            .expect("unable to create query");
+
+    let ids = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| {
+            let id = match row
+                .take_feature_as(&"id".to_owned(), &DataType::Uuid)
+                .expect("unable to convert feature")
+            {
+                Some(OwnedValueUnion::Uuid(id)) => id,
+                value => panic!("unexpected value {value:?}"),
+            };
+            let id_type = match row
+                .take_feature_as(&"id_type".to_owned(), &DataType::String)
+                .expect("unable to convert feature")
+            {
+                Some(OwnedValueUnion::String(id_type)) => id_type,
+                value => panic!("unexpected value {value:?}"),
+            };
+
+            (id, id_type)
+        })
+        .collect();
+
+    ids
+}
+
+fn id() -> Uuid {
+    Uuid::parse_str(ID).expect("invalid uuid")
+}
+
+#[test]
+fn test_uuid_text_storage_by_default() {
+    let connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE users (id)");
+
+    assert_eq!(connection.uuid_storage(), UuidStorage::Text);
+
+    insert_id(&connection, &id());
+
+    assert_eq!(select_ids(&connection), vec![(id(), "text".to_owned())]);
+
+    let mut query = SqliteQuery::new_with_iterator(&connection, "SELECT id FROM users")
+        .expect("unable to create query");
+    let row = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .next()
+        .expect("expected a row");
+    let stored = row
+        .take_feature(&"id".to_owned())
+        .expect("unable to take feature")
+        .expect("feature cannot be null");
+
+    assert_eq!(<&String>::try_from(stored).expect("id is not text"), ID);
+}
+
+#[test]
+fn test_uuid_blob_storage() {
+    let mut connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE users (id)");
+
+    connection.set_uuid_storage(UuidStorage::Blob);
+    insert_id(&connection, &id());
+
+    assert_eq!(select_ids(&connection), vec![(id(), "blob".to_owned())]);
+}
+
+#[test]
+fn test_uuid_decodes_mixed_storage() {
+    let mut connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE users (id)");
+
+    let other_id = Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+    insert_id(&connection, &id());
+    connection.set_uuid_storage(UuidStorage::Blob);
+    insert_id(&connection, &other_id);
+
+    assert_eq!(
+        select_ids(&connection),
+        vec![(id(), "text".to_owned()), (other_id, "blob".to_owned())]
+    );
+}
+
+#[test]
+fn test_uuid_rejects_malformed_values() {
+    let text = "not-a-uuid".to_owned();
+    let bytes = vec![0u8; 15];
+
+    assert!(ValueUnion::String(&text)
+        .convert_to(&DataType::Uuid)
+        .is_err());
+    assert!(ValueUnion::Bytestring(&bytes)
+        .convert_to(&DataType::Uuid)
+        .is_err());
+}
+
+#[test]
+fn test_uuid_page_cursor() {
+    let cursor = PageCursor::new(vec![OwnedValueUnion::Uuid(id())]);
+
+    assert_eq!(
+        PageCursor::from_token(&cursor.to_token()).expect("unable to decode cursor"),
+        cursor
+    );
+}
@@ -0,0 +1,184 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+use std::{cmp::Ordering, time::Duration};
+
+use bedrock_orm::{
+    domain::{DataType, OwnedValueUnion, ValueUnion},
+    Error,
+};
+use chrono::{DateTime, NaiveDate};
+
+#[test]
+fn test_numeric_equality_across_variants() {
+    assert_eq!(ValueUnion::U8(1), ValueUnion::I64(1));
+    assert_eq!(ValueUnion::I32(-3), ValueUnion::F64(-3.0));
+    assert_eq!(ValueUnion::U128(u128::MAX), ValueUnion::U128(u128::MAX));
+    assert_ne!(ValueUnion::I64(1), ValueUnion::Bool(true));
+    assert_ne!(ValueUnion::F64(f64::NAN), ValueUnion::F64(f64::NAN));
+    assert_eq!(ValueUnion::Null, ValueUnion::Null);
+}
+
+#[test]
+fn test_numeric_ordering_across_variants() {
+    assert!(ValueUnion::U8(1) < ValueUnion::F32(1.5));
+    assert!(ValueUnion::F32(1.5) < ValueUnion::I16(2));
+    assert!(ValueUnion::I64(-1) < ValueUnion::U64(0));
+    assert!(ValueUnion::I128(i128::MAX) < ValueUnion::U128(u128::MAX));
+    assert!(ValueUnion::F64(-1.5) < ValueUnion::I8(-1));
+    // Precision that would be lost by converting the integer to a float
+    assert!(ValueUnion::I64((1 << 53) + 1) > ValueUnion::F64((1u64 << 53) as f64));
+    assert_eq!(
+        ValueUnion::I64(1).partial_cmp(&ValueUnion::F64(f64::NAN)),
+        None
+    );
+    assert_eq!(
+        ValueUnion::I64(1).partial_cmp(&ValueUnion::String(&"1".to_owned())),
+        None
+    );
+}
+
+#[test]
+fn test_ordering_of_other_variants() {
+    let apple = "apple".to_owned();
+    let banana = "banana".to_owned();
+    let utc = DateTime::parse_from_rfc3339("2023-05-01T12:00:00Z")
+        .expect("invalid datetime")
+        .into();
+    let offset =
+        DateTime::parse_from_rfc3339("2023-05-01T13:00:00+02:00").expect("invalid datetime");
+
+    assert!(ValueUnion::String(&apple) < ValueUnion::String(&banana));
+    assert!(ValueUnion::DateTimeTz(&offset) < ValueUnion::DateTimeUtc(&utc));
+    assert!(ValueUnion::Duration(&Duration::from_secs(1)) > ValueUnion::Duration(&Duration::ZERO));
+
+    let short = [OwnedValueUnion::I64(1)];
+    let long = [OwnedValueUnion::I64(1), OwnedValueUnion::I64(2)];
+
+    assert!(ValueUnion::List(&short) < ValueUnion::List(&long));
+    assert_eq!(
+        ValueUnion::List(&[OwnedValueUnion::U8(1)]),
+        ValueUnion::List(&short)
+    );
+}
+
+#[test]
+fn test_owned_values_compare_structurally_or_semantically() {
+    assert_ne!(OwnedValueUnion::U8(1), OwnedValueUnion::I64(1));
+    assert!(OwnedValueUnion::U8(1).semantically_eq(&OwnedValueUnion::I64(1)));
+    assert!(!OwnedValueUnion::F64(f64::NAN).semantically_eq(&OwnedValueUnion::F64(f64::NAN)));
+    assert_eq!(
+        OwnedValueUnion::F32(1.5).semantic_cmp(&OwnedValueUnion::I16(2)),
+        Some(Ordering::Less)
+    );
+    assert_eq!(
+        OwnedValueUnion::I64(1).semantic_cmp(&OwnedValueUnion::String("1".to_owned())),
+        None
+    );
+    assert!(OwnedValueUnion::List(vec![OwnedValueUnion::U8(1)])
+        .semantically_eq(&OwnedValueUnion::List(vec![OwnedValueUnion::I64(1)])));
+    assert_eq!(
+        OwnedValueUnion::List(vec![OwnedValueUnion::I64(1)]).semantic_cmp(&OwnedValueUnion::List(
+            vec![OwnedValueUnion::I64(1), OwnedValueUnion::I64(2)]
+        )),
+        Some(Ordering::Less)
+    );
+}
+
+#[test]
+fn test_display_as_sql_literals() {
+    let text = "it's".to_owned();
+    let bytes = [0x0a, 0xff];
+    let date = NaiveDate::from_ymd_opt(2023, 5, 1).expect("invalid date");
+    let list = [OwnedValueUnion::I64(1), OwnedValueUnion::Null];
+
+    assert_eq!(ValueUnion::Null.to_string(), "NULL");
+    assert_eq!(ValueUnion::Bool(true).to_string(), "TRUE");
+    assert_eq!(ValueUnion::I64(-42).to_string(), "-42");
+    assert_eq!(ValueUnion::F64(1.0).to_string(), "1.0");
+    assert_eq!(
+        ValueUnion::F64(f64::NEG_INFINITY).to_string(),
+        "'-Infinity'"
+    );
+    assert_eq!(ValueUnion::String(&text).to_string(), "'it''s'");
+    assert_eq!(ValueUnion::Bytestring(&bytes).to_string(), "X'0AFF'");
+    assert_eq!(ValueUnion::Date(&date).to_string(), "'2023-05-01'");
+    assert_eq!(ValueUnion::List(&list).to_string(), "(1, NULL)");
+    assert_eq!(OwnedValueUnion::U16(7).to_string(), "7");
+}
+
+#[test]
+fn test_parse_with_data_type() {
+    assert_eq!(
+        OwnedValueUnion::parse("42", &DataType::U8).expect("unable to parse value"),
+        OwnedValueUnion::U8(42)
+    );
+    assert_eq!(
+        OwnedValueUnion::parse("-1.25", &DataType::F64).expect("unable to parse value"),
+        OwnedValueUnion::F64(-1.25)
+    );
+    assert_eq!(
+        OwnedValueUnion::parse("true", &DataType::Bool).expect("unable to parse value"),
+        OwnedValueUnion::Bool(true)
+    );
+    assert_eq!(
+        OwnedValueUnion::parse("0aFF", &DataType::Bytestring).expect("unable to parse value"),
+        OwnedValueUnion::Bytestring(vec![0x0a, 0xff])
+    );
+    assert_eq!(
+        OwnedValueUnion::parse("2023-05-01", &DataType::Date).expect("unable to parse value"),
+        OwnedValueUnion::Date(NaiveDate::from_ymd_opt(2023, 5, 1).expect("invalid date"))
+    );
+    assert_eq!(
+        OwnedValueUnion::parse("1500", &DataType::Duration).expect("unable to parse value"),
+        OwnedValueUnion::Duration(Duration::from_millis(1500))
+    );
+    assert_eq!(
+        OwnedValueUnion::parse("NULL", &DataType::I32.nullable()).expect("unable to parse value"),
+        OwnedValueUnion::Null
+    );
+    assert_eq!(
+        OwnedValueUnion::parse("7", &DataType::I32.nullable()).expect("unable to parse value"),
+        OwnedValueUnion::I32(7)
+    );
+
+    for (text, data_type) in [
+        ("256", DataType::U8),
+        ("NULL", DataType::I32),
+        ("+f", DataType::Bytestring),
+        ("yes", DataType::Bool),
+    ] {
+        assert!(matches!(
+            OwnedValueUnion::parse(text, &data_type),
+            Err(Error::InvalidValueText { .. })
+        ));
+    }
+}
+
+#[cfg(feature = "decimal")]
+#[test]
+fn test_decimal_comparison() {
+    use std::{cmp::Ordering, str::FromStr};
+
+    let decimal = rust_decimal::Decimal::from_str("2.50").expect("invalid decimal");
+
+    assert_eq!(ValueUnion::Decimal(&decimal), ValueUnion::F64(2.5));
+    assert!(ValueUnion::Decimal(&decimal) > ValueUnion::I64(2));
+    assert!(ValueUnion::Decimal(&decimal) < ValueUnion::U128(u128::MAX));
+    assert_eq!(
+        ValueUnion::Decimal(&decimal).partial_cmp(&ValueUnion::I128(i128::MIN)),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(ValueUnion::Decimal(&decimal).to_string(), "2.50");
+}
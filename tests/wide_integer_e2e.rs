@@ -0,0 +1,178 @@
+// Copyright (c) 2023 Sophie Katz
+//
+// This file is part of Bedrock ORM.
+//
+// Bedrock ORM is free software: you can redistribute it and/or modify it under the terms of the
+// GNU General Public License as published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// Bedrock ORM is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without
+// even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Bedrock ORM. If
+// not, see <https://www.gnu.org/licenses/>.
+use bedrock_orm::{
+    database_providers::{IntegerOverflowPolicy, SqliteConnection, SqliteQuery},
+    domain::{DataType, OwnedValueUnion, ValueUnion},
+    query_execution::{ExecuteQuery, InjectFeatures, PageCursor, TakeFeatures},
+    Error,
+};
+
+fn connect_memory() -> SqliteConnection {
+    SqliteConnection::connect_memory().expect("unable to connect to sqlite database in memory")
+}
+
+fn execute_ok(connection: &SqliteConnection, query_text: &str) {
+    let mut query =
+        SqliteQuery::new_without_results(connection, query_text).expect("unable to create query");
+
+    connection
+        .execute_without_results(&mut query)
+        .expect("unable to execute query");
+}
+
+fn insert_value(connection: &SqliteConnection, value: ValueUnion) -> bedrock_orm::Result<()> {
+    let mut query =
+        SqliteQuery::new_without_results(connection, "INSERT INTO numbers VALUES (:value)")
+            .expect("unable to create query");
+
+    query.inject_feature(&"value".to_owned(), &value)?;
+
+    connection.execute_without_results(&mut query)
+}
+
+fn select_values(
+    connection: &SqliteConnection,
+    data_type: &DataType,
+) -> Vec<(OwnedValueUnion, String)> {
+    let mut query = SqliteQuery::new_with_iterator(
+        connection,
+        "SELECT value, typeof(value) AS value_type FROM numbers",
+    )
+    .expect("unable to create query");
+
+    let values = connection
+        .execute_with_iterator(&mut query)
+        .expect("unable to execute query")
+        .map(|row| {
+            let value = row
+                .take_feature_as(&"value".to_owned(), data_type)
+                .expect("unable to convert feature")
+                .expect("feature cannot be null");
+            let value_type = match row
+                .take_feature_as(&"value_type".to_owned(), &DataType::String)
+                .expect("unable to convert feature")
+            {
+                Some(OwnedValueUnion::String(value_type)) => value_type,
+                value => panic!("unexpected value {value:?}"),
+            };
+
+            (value, value_type)
+        })
+        .collect();
+
+    values
+}
+
+#[test]
+fn test_u64_overflow_text_by_default() {
+    let connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE numbers (value)");
+
+    assert_eq!(
+        connection.integer_overflow_policy(),
+        IntegerOverflowPolicy::Text
+    );
+
+    insert_value(&connection, ValueUnion::U64(7)).expect("unable to insert value");
+    insert_value(&connection, ValueUnion::U64(u64::MAX)).expect("unable to insert value");
+
+    assert_eq!(
+        select_values(&connection, &DataType::U64),
+        vec![
+            (OwnedValueUnion::U64(7), "integer".to_owned()),
+            (OwnedValueUnion::U64(u64::MAX), "text".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_u64_overflow_blob() {
+    let mut connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE numbers (value)");
+
+    connection.set_integer_overflow_policy(IntegerOverflowPolicy::Blob);
+    insert_value(&connection, ValueUnion::U64(u64::MAX - 1)).expect("unable to insert value");
+
+    assert_eq!(
+        select_values(&connection, &DataType::U64),
+        vec![(OwnedValueUnion::U64(u64::MAX - 1), "blob".to_owned())]
+    );
+}
+
+#[test]
+fn test_u64_overflow_error() {
+    let mut connection = connect_memory();
+    execute_ok(&connection, "CREATE TABLE numbers (value)");
+
+    connection.set_integer_overflow_policy(IntegerOverflowPolicy::Error);
+
+    insert_value(&connection, ValueUnion::U64(i64::MAX as u64)).expect("unable to insert value");
+    assert!(matches!(
+        insert_value(&connection, ValueUnion::U64(i64::MAX as u64 + 1)),
+        Err(Error::IntegerOutOfRange { .. })
+    ));
+}
+
+#[test]
+fn test_128_bit_round_trip() {
+    for policy in [IntegerOverflowPolicy::Text, IntegerOverflowPolicy::Blob] {
+        let mut connection = connect_memory();
+        execute_ok(&connection, "CREATE TABLE numbers (value)");
+        connection.set_integer_overflow_policy(policy);
+
+        for value in [i128::MIN, -1, i128::MAX] {
+            insert_value(&connection, ValueUnion::I128(value)).expect("unable to insert value");
+        }
+
+        assert_eq!(
+            select_values(&connection, &DataType::I128)
+                .into_iter()
+                .map(|(value, _)| value)
+                .collect::<Vec<_>>(),
+            vec![
+                OwnedValueUnion::I128(i128::MIN),
+                OwnedValueUnion::I128(-1),
+                OwnedValueUnion::I128(i128::MAX),
+            ]
+        );
+
+        execute_ok(&connection, "DELETE FROM numbers");
+
+        for value in [0, u128::MAX] {
+            insert_value(&connection, ValueUnion::U128(value)).expect("unable to insert value");
+        }
+
+        assert_eq!(
+            select_values(&connection, &DataType::U128)
+                .into_iter()
+                .map(|(value, _)| value)
+                .collect::<Vec<_>>(),
+            vec![OwnedValueUnion::U128(0), OwnedValueUnion::U128(u128::MAX)]
+        );
+    }
+}
+
+#[test]
+fn test_128_bit_page_cursor() {
+    let cursor = PageCursor::new(vec![
+        OwnedValueUnion::I128(i128::MIN),
+        OwnedValueUnion::U128(u128::MAX),
+    ]);
+
+    assert_eq!(
+        PageCursor::from_token(&cursor.to_token()).expect("unable to decode cursor"),
+        cursor
+    );
+}